@@ -0,0 +1,32 @@
+#import bevy_pbr::forward_io::VertexOutput
+
+struct Beam {
+    material_color: vec4<f32>,
+    time: f32,
+    duration: f32,
+    _wasm_padding: vec2<f32>,
+}
+
+@group(2) @binding(0) var<uniform> beam: Beam;
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    // Skip if the time is up
+    if beam.time > beam.duration {
+        return vec4f(beam.material_color.rgb, 0.0);
+    }
+
+    let scaled_time = beam.time / beam.duration;
+
+    // Distance from the middle of the beam's width, 0.0 in the middle and 1.0 at the sides
+    let dist = abs(in.uv.x * 2.0 - 1.0);
+
+    // The beam narrows as it fades, with a bright core
+    let width = 1.0 - scaled_time;
+    let intensity = (1.0 - smoothstep(0.0, width, dist)) + (1.0 - step(0.15 * width, dist));
+
+    // Fade out over the duration
+    let fade = pow(1.0 - scaled_time, 2.0);
+
+    return vec4<f32>(beam.material_color.rgb * (1.0 + intensity), intensity * fade);
+}
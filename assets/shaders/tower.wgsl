@@ -4,11 +4,23 @@
     pbr_functions::apply_pbr_lighting,
 }
 
+struct Tower {
+    highlight_color: vec4<f32>,
+    fuse: f32,
+    charge: f32,
+    health: f32,
+    _wasm_padding: f32,
+}
+
 @group(2) @binding(100) var detail_texture: texture_2d<f32>;
 @group(2) @binding(101) var detail_texture_sampler: sampler;
+@group(2) @binding(102) var<uniform> tower: Tower;
 
 const BASE_COLOR: vec4f = vec4f(0.0, 0.0, 0.0, 1.0);
-const HIGHLIGHT_COLOR: vec4f = vec4f(0.2, 0.8, 0.2, 1.0);
+const FUSE_COLOR: vec4f = vec4f(1.0, 0.45, 0.1, 1.0);
+const CHARGING_BRIGHTNESS: f32 = 0.35;
+const DAMAGE_COLOR: vec4f = vec4f(0.8, 0.05, 0.0, 1.0);
+const PI: f32 = 3.14159265;
 
 @fragment
 fn fragment(
@@ -16,6 +28,9 @@ fn fragment(
     @builtin(front_facing) is_front: bool,
 ) -> FragmentOutput {
     var pbr_input = pbr_input_from_standard_material(in, is_front);
+    // Damaged towers are tinted red, more so the less health they have left
+    let damage = (1.0 - tower.health) * 0.6;
+    pbr_input.material.base_color = mix(pbr_input.material.base_color, DAMAGE_COLOR, damage);
 
     var out: FragmentOutput;
     out.color = apply_pbr_lighting(pbr_input);
@@ -24,14 +39,38 @@ fn fragment(
         // This isn't the top face, so it's a side
         if in.uv.y > 0.9 {
             // Draw a line along the top of the side
-            out.color = HIGHLIGHT_COLOR;
+            out.color = tower.highlight_color;
         }
         return out;
     }
 
+    // Towers that are out of charges are dimmed until they get one back
+    var highlight_color = tower.highlight_color;
+    if tower.charge < 1.0 {
+        highlight_color = vec4f(highlight_color.rgb * CHARGING_BRIGHTNESS, highlight_color.a);
+    }
+
     let sample = textureSample(detail_texture, detail_texture_sampler, in.uv);
     if sample.r > 0.5 {
-        out.color = HIGHLIGHT_COLOR;
+        out.color = highlight_color;
+    }
+
+    let offset = in.uv - vec2f(0.5, 0.5);
+    let dist = length(offset);
+    let turn = (atan2(offset.x, -offset.y) + PI) / (2.0 * PI);
+
+    if tower.charge < 1.0 {
+        // Draw an outer ring that fills clockwise as the tower recharges
+        if dist > 0.38 && dist < 0.42 && turn < tower.charge {
+            out.color = tower.highlight_color;
+        }
+    }
+
+    if tower.fuse > 0.0 {
+        // Draw a ring around the middle that empties clockwise as the fuse burns down
+        if dist > 0.28 && dist < 0.34 && turn < tower.fuse {
+            out.color = FUSE_COLOR;
+        }
     }
 
     return out;
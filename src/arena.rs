@@ -3,12 +3,15 @@ use bevy::{
     prelude::*,
     render::mesh::{Indices, PrimitiveTopology},
 };
-use hexx::{ColumnMeshBuilder, Hex, HexLayout, HexOrientation};
+use hexx::{ColumnMeshBuilder, EdgeDirection, Hex, HexLayout, HexOrientation};
+use rand::Rng;
 
 use crate::{
     AppState, GameState,
     arena_index::ArenaIndex,
     force::{Force, ForceReceiver},
+    game_assets::GameAssets,
+    rng::{RngSet, RunRng},
 };
 
 pub const ARENA_COLUMN_HEIGHT: f32 = 100.0;
@@ -26,7 +29,7 @@ impl Plugin for ArenaPlugin {
                 ..default()
             },
         })
-        .add_systems(OnEnter(AppState::InGame), setup_arena)
+        .add_systems(OnEnter(AppState::InGame), setup_arena.after(RngSet))
         .add_systems(OnExit(AppState::InGame), cleanup_arena)
         .add_systems(
             Update,
@@ -44,6 +47,13 @@ pub struct Arena {
 
 impl Arena {
     pub const RADIUS: u32 = ARENA_RADIUS;
+
+    /// The hexes in a straight line from `origin` (not included) to the edge of the arena.
+    pub fn line_to_edge(origin: Hex, direction: EdgeDirection) -> impl Iterator<Item = Hex> {
+        (1..)
+            .map(move |distance| origin + direction * distance)
+            .take_while(|hex| hex.unsigned_distance_to(Hex::ZERO) <= Self::RADIUS)
+    }
 }
 
 #[derive(Component)]
@@ -61,30 +71,23 @@ pub enum ColumnKind {
     Wall,
 }
 
+/// Spawns the arena columns, meshes are only attached when `GameAssets` is available (i.e. not
+/// when running headless).
 fn setup_arena(
     mut commands: Commands,
     arena: Res<Arena>,
+    game_assets: Option<Res<GameAssets>>,
     mut arena_index: ResMut<ArenaIndex>,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut rng: ResMut<RunRng>,
 ) {
-    let mesh_handle = meshes.add(hex_column_mesh(&arena.layout, ARENA_COLUMN_HEIGHT));
-    let material_handle = materials.add(StandardMaterial {
-        base_color: Color::srgb(0.0, 0.0, 0.0),
-        perceptual_roughness: 1.0,
-        ..default()
-    });
-
     for hex in hexx::shapes::hexagon(Hex::ZERO, ARENA_RADIUS + ARENA_WALL_DEPTH) {
         let pos = arena.layout.hex_to_world_pos(hex);
         let dist = hex.unsigned_distance_to(Hex::ZERO);
-        if dist <= ARENA_RADIUS {
+        let id = if dist <= ARENA_RADIUS {
             // Within the arena
-            let offset = rand::random_range(-0.3..0.0);
+            let offset = rng.cosmetics.random_range(-0.3..0.0);
             let id = commands
                 .spawn((
-                    Mesh3d(mesh_handle.clone()),
-                    MeshMaterial3d(material_handle.clone()),
                     Transform::from_xyz(pos.x, offset, pos.y),
                     ArenaColumn {
                         hex,
@@ -98,18 +101,26 @@ fn setup_arena(
                 .id();
             // Insert the column into the arena index
             arena_index.column_index.insert(hex, id);
+            id
         } else {
             // Outside the arena (wall)
-            let offset = 4.0 + rand::random_range(0.0..3.0);
-            commands.spawn((
-                Mesh3d(mesh_handle.clone()),
-                MeshMaterial3d(material_handle.clone()),
-                Transform::from_xyz(pos.x, offset, pos.y),
-                ArenaColumn {
-                    hex,
-                    offset,
-                    kind: ColumnKind::Wall,
-                },
+            let offset = 4.0 + rng.cosmetics.random_range(0.0..3.0);
+            commands
+                .spawn((
+                    Transform::from_xyz(pos.x, offset, pos.y),
+                    ArenaColumn {
+                        hex,
+                        offset,
+                        kind: ColumnKind::Wall,
+                    },
+                ))
+                .id()
+        };
+
+        if let Some(game_assets) = &game_assets {
+            commands.entity(id).insert((
+                Mesh3d(game_assets.arena_column_mesh.clone()),
+                MeshMaterial3d(game_assets.arena_column_material.clone()),
             ));
         }
     }
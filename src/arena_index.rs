@@ -15,8 +15,9 @@ impl Plugin for ArenaIndexPlugin {
         app.init_resource::<ArenaIndex>()
             .add_systems(OnEnter(AppState::InGame), reset_index)
             .add_systems(
-                Update,
+                FixedUpdate,
                 update_arena_hex_and_index
+                    .in_set(ArenaIndexSet)
                     .run_if(in_state(AppState::InGame))
                     .run_if(in_state(GameState::Running)),
             )
@@ -25,6 +26,9 @@ impl Plugin for ArenaIndexPlugin {
     }
 }
 
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ArenaIndexSet;
+
 #[derive(Resource)]
 pub struct ArenaIndex {
     /// A map of hexes to all contained `ArenaHex` entities.
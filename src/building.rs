@@ -1,13 +1,19 @@
-use bevy::{input::common_conditions::input_just_pressed, prelude::*};
-use hexx::Hex;
+use bevy::{input::mouse::MouseWheel, prelude::*};
+use hexx::{EdgeDirection, Hex};
 
 use crate::{
     AppState, GameState,
     arena::Arena,
     arena_index::ArenaIndex,
+    controls::{Actions, InputAction, action_just_pressed},
     game_assets::GameAssets,
+    health::Health,
     pointer_tracking::{PointerChangedHexEvent, PointerPosition},
-    tower::{PlaceTowerCommand, Tower, TowerAction, TowerKind},
+    replay::{ReplayAction, ReplayRecorder, is_replaying},
+    tower::{
+        MAX_TOWER_LEVEL, PlaceTowerCommand, Tower, TowerAction, TowerCharges, TowerId, TowerList,
+        TowerRegistry,
+    },
 };
 
 const PLACEHOLDER_HEIGHT: f32 = 2.0;
@@ -16,26 +22,31 @@ pub struct BuildingPlugin;
 
 impl Plugin for BuildingPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<BuildingSettings>()
-            .add_event::<RedrawPlacementEvent>()
-            .add_event::<BuildingsUpdatedEvent>()
+        app.add_event::<RedrawPlacementEvent>()
             .add_systems(OnEnter(GameState::Building), setup_building)
             .add_systems(OnExit(GameState::Building), cleanup_building)
             .add_systems(OnExit(AppState::InGame), cleanup_building)
             .add_systems(
                 Update,
-                select_building
+                (
+                    select_building,
+                    start_pick_up.run_if(action_just_pressed(InputAction::PickUp)),
+                )
                     .in_set(BuildingSet)
+                    .run_if(not(is_replaying))
                     .run_if(in_state(AppState::InGame))
                     .run_if(in_state(GameState::Running)),
             )
             .add_systems(
                 Update,
                 (
-                    cancel_building.run_if(input_just_pressed(KeyCode::Escape)),
+                    cancel_building.run_if(action_just_pressed(InputAction::Cancel)),
                     select_building,
-                    place_building.run_if(input_just_pressed(MouseButton::Left)),
-                    redraw_placement.run_if(
+                    start_pick_up.run_if(action_just_pressed(InputAction::PickUp)),
+                    rotate_building,
+                    place_building.run_if(action_just_pressed(InputAction::Place)),
+                    // Runs after a picked up tower has been selected
+                    redraw_placement.after(place_building).run_if(
                         on_event::<RedrawPlacementEvent>.or(on_event::<PointerChangedHexEvent>),
                     ),
                 )
@@ -46,24 +57,104 @@ impl Plugin for BuildingPlugin {
     }
 }
 
+/// Tracks the player's collected towers, this is part of the simulation so it's available when
+/// running headless.
+pub struct BuildingSettingsPlugin;
+
+impl Plugin for BuildingSettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BuildingSettings>()
+            .add_event::<BuildingsUpdatedEvent>()
+            .add_systems(OnEnter(AppState::InGame), reset_building_settings);
+    }
+}
+
 #[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct BuildingSet;
 
+/// A tower in the player's collection, waiting to be built.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CollectedTower {
+    pub id: TowerId,
+    /// See `Tower::level`.
+    pub level: u8,
+    /// Set when a damaged or drained tower is picked up, so building it again doesn't repair it.
+    /// Worn towers don't merge in the collection.
+    pub wear: Option<TowerWear>,
+}
+
+impl CollectedTower {
+    pub fn new(id: TowerId) -> Self {
+        Self {
+            id,
+            level: 1,
+            wear: None,
+        }
+    }
+}
+
+/// The health and charges a picked up tower had left.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TowerWear {
+    pub health: u16,
+    pub charges: u32,
+}
+
 /// Keeps track of the collected towers, and the tower being built.
 #[derive(Resource)]
 pub struct BuildingSettings {
     /// All the player's collected towers that can be built.
-    pub towers: Vec<TowerKind>,
-    /// Index of the tower currently being built, when None no tower is currently being built.
+    pub towers: Vec<CollectedTower>,
+    /// Index of the tower currently being built, when None no tower is currently being built and
+    /// clicking a placed tower chooses it to be moved.
     pub selected_tower: Option<usize>,
+    /// The hex of a placed tower chosen to be moved. It stays where it is while its new placement
+    /// is previewed, clicking another hex moves it and clicking it again picks it up.
+    pub moving_tower: Option<Hex>,
+    /// The rotation the next tower is built with, see `Tower::rotation`.
+    pub rotation: u8,
 }
 
 impl BuildingSettings {
-    pub fn get_selected(&self) -> Option<TowerKind> {
+    pub fn get_selected(&self) -> Option<CollectedTower> {
         self.selected_tower
             .map(|i| self.towers.get(i).map(Clone::clone))
             .flatten()
     }
+
+    /// Adds a tower to the collection, merging it with an identical tower into one a level
+    /// higher. The merged tower can merge again, so collecting can upgrade more than once.
+    pub fn add(&mut self, mut tower: CollectedTower) {
+        while let Some(index) = self.merge_index(&tower) {
+            tower = self.towers.remove(index);
+            tower.level += 1;
+            self.selected_tower = None;
+        }
+        self.towers.push(tower);
+    }
+
+    /// The level `tower` would have after being added to the collection.
+    pub fn level_after_adding(&self, tower: &CollectedTower) -> u8 {
+        let mut level = tower.level;
+        while self
+            .merge_index(&CollectedTower {
+                id: tower.id.clone(),
+                level,
+                wear: tower.wear.clone(),
+            })
+            .is_some()
+        {
+            level += 1;
+        }
+        level
+    }
+
+    fn merge_index(&self, tower: &CollectedTower) -> Option<usize> {
+        if tower.level >= MAX_TOWER_LEVEL || tower.wear.is_some() {
+            return None;
+        }
+        self.towers.iter().position(|collected| collected == tower)
+    }
 }
 
 impl Default for BuildingSettings {
@@ -71,6 +162,8 @@ impl Default for BuildingSettings {
         BuildingSettings {
             towers: vec![],
             selected_tower: None,
+            moving_tower: None,
+            rotation: 0,
         }
     }
 }
@@ -91,17 +184,180 @@ pub struct RedrawPlacementEvent;
 pub struct BuildingsUpdatedEvent;
 
 pub struct AddTowerCommand {
-    kind: TowerKind,
+    id: TowerId,
 }
 
 impl Command for AddTowerCommand {
     fn apply(self, world: &mut World) -> () {
         let mut settings = world.get_resource_mut::<BuildingSettings>().unwrap();
-        settings.towers.push(self.kind);
+        settings.add(CollectedTower::new(self.id));
         world.send_event(BuildingsUpdatedEvent);
     }
 }
 
+/// Builds one of the player's collected towers, removing it from `BuildingSettings`. Building
+/// onto an identical placed tower upgrades it instead.
+///
+/// All tower building goes through this command so it can be recorded and replayed.
+pub struct BuildTowerCommand {
+    /// The index of the tower in `BuildingSettings::towers`.
+    pub index: usize,
+    pub hex: Hex,
+    /// See `Tower::rotation`.
+    pub rotation: u8,
+}
+
+impl Command for BuildTowerCommand {
+    fn apply(self, world: &mut World) -> () {
+        let Some(collected) = world
+            .resource::<BuildingSettings>()
+            .towers
+            .get(self.index)
+            .cloned()
+        else {
+            warn!(
+                index = self.index,
+                "Tried building a tower that hasn't been collected"
+            );
+            return;
+        };
+
+        let placed = world
+            .resource::<ArenaIndex>()
+            .tower_index
+            .get(&self.hex)
+            .copied();
+        let merge_target = placed.filter(|entity| {
+            world
+                .get::<Tower>(*entity)
+                .is_some_and(|tower| tower.can_merge(&collected.id, collected.level))
+        });
+        if merge_target.is_none() && world.resource::<ArenaIndex>().is_occupied(&self.hex) {
+            warn!(hex=?self.hex, "Hex is occupied, tower can't be built");
+            return;
+        }
+
+        // Remove the tower from the player's collection.
+        let mut settings = world.resource_mut::<BuildingSettings>();
+        settings.towers.remove(self.index);
+        settings.selected_tower = None;
+
+        if let Some(entity) = merge_target {
+            upgrade_tower(world, entity);
+        } else {
+            PlaceTowerCommand {
+                hex: self.hex,
+                tower: Tower {
+                    id: collected.id,
+                    rotation: self.rotation,
+                    level: collected.level,
+                },
+            }
+            .apply(world);
+
+            // Picked up towers keep the health and charges they had left
+            let placed = world
+                .resource::<ArenaIndex>()
+                .tower_index
+                .get(&self.hex)
+                .copied();
+            if let (Some(wear), Some(entity)) = (collected.wear, placed) {
+                if let Some(mut health) = world.get_mut::<Health>(entity) {
+                    health.current = wear.health.min(health.max);
+                }
+                if let Some(mut charges) = world.get_mut::<TowerCharges>(entity) {
+                    charges.set_charges(wear.charges);
+                }
+            }
+        }
+
+        world.send_event(BuildingsUpdatedEvent);
+
+        if let Some(mut recorder) = world.get_resource_mut::<ReplayRecorder>() {
+            recorder.record(ReplayAction::BuildTower {
+                index: self.index,
+                hex: self.hex,
+                rotation: self.rotation,
+            });
+        }
+    }
+}
+
+/// Removes a placed tower and returns it to the front of `BuildingSettings::towers`, selecting it
+/// so it can be moved to another hex.
+///
+/// Recorded and replayed in the same way as `BuildTowerCommand`.
+pub struct PickUpTowerCommand {
+    pub hex: Hex,
+}
+
+impl Command for PickUpTowerCommand {
+    fn apply(self, world: &mut World) -> () {
+        let Some(&entity) = world.resource::<ArenaIndex>().tower_index.get(&self.hex) else {
+            warn!(hex=?self.hex, "No tower to pick up");
+            return;
+        };
+
+        let Some(tower) = world.get::<Tower>(entity) else {
+            warn!(hex=?self.hex, "Tower index contains an entity that is not a tower");
+            return;
+        };
+        let health = world.get::<Health>(entity);
+        let charges = world.get::<TowerCharges>(entity);
+        let is_worn = health.is_some_and(|health| health.current < health.max)
+            || charges.is_some_and(|charges| !charges.is_full());
+        let collected = CollectedTower {
+            id: tower.id.clone(),
+            level: tower.level,
+            wear: is_worn.then(|| TowerWear {
+                // Missing components are restored in full
+                health: health.map_or(u16::MAX, |health| health.current),
+                charges: charges.map_or(u32::MAX, TowerCharges::charges),
+            }),
+        };
+        let rotation = tower.rotation;
+
+        world
+            .resource_mut::<ArenaIndex>()
+            .tower_index
+            .remove(&self.hex);
+        world.despawn(entity);
+
+        let mut settings = world.resource_mut::<BuildingSettings>();
+        // Not merged, so the player can put it back where it was
+        settings.towers.insert(0, collected);
+        settings.selected_tower = Some(0);
+        settings.rotation = rotation;
+
+        world.send_event(BuildingsUpdatedEvent);
+
+        if let Some(mut recorder) = world.get_resource_mut::<ReplayRecorder>() {
+            recorder.record(ReplayAction::PickUpTower { hex: self.hex });
+        }
+    }
+}
+
+/// Raises a placed tower's level, keeping its rotation.
+fn upgrade_tower(world: &mut World, entity: Entity) {
+    let Some(mut tower) = world.get_mut::<Tower>(entity) else {
+        return;
+    };
+    tower.level += 1;
+    let (id, level) = (tower.id.clone(), tower.level);
+
+    // Visuals are only available when not running headless
+    if let Some(material) = world
+        .get_resource::<GameAssets>()
+        .map(|game_assets| game_assets.tower_material(&id, level))
+    {
+        world.entity_mut(entity).insert(MeshMaterial3d(material));
+    }
+}
+
+pub fn reset_building_settings(mut settings: ResMut<BuildingSettings>) {
+    *settings = BuildingSettings::default();
+}
+
 fn setup_building(
     mut commands: Commands,
     arena: Res<Arena>,
@@ -112,8 +368,8 @@ fn setup_building(
 ) {
     let world_pos = arena.layout.hex_to_world_pos(pointer_pos.hex);
 
-    let material = if let Some(kind) = settings.get_selected() {
-        game_assets.tower_placeholder_materials.get(&kind)
+    let material = if let Some(tower) = settings.get_selected() {
+        game_assets.tower_placeholder_materials.get(&tower.id)
     } else {
         game_assets.tower_placeholder_empty_material.clone()
     };
@@ -122,7 +378,8 @@ fn setup_building(
         BuildingPlaceholder,
         Mesh3d(game_assets.tower_placeholder_mesh.clone()),
         MeshMaterial3d(material),
-        Transform::from_xyz(world_pos.x, PLACEHOLDER_HEIGHT, world_pos.y),
+        Transform::from_xyz(world_pos.x, PLACEHOLDER_HEIGHT, world_pos.y)
+            .with_rotation(Tower::mesh_rotation(settings.rotation)),
     ));
 
     evw_redraw_placement.write(RedrawPlacementEvent);
@@ -130,9 +387,12 @@ fn setup_building(
 
 fn cleanup_building(
     mut commands: Commands,
+    mut settings: ResMut<BuildingSettings>,
     q_graphic: Query<Entity, With<BuildingPlaceholder>>,
     q_highlight: Query<Entity, With<HighlightedHex>>,
 ) {
+    settings.moving_tower = None;
+
     for entity in q_graphic {
         commands.entity(entity).try_despawn();
     }
@@ -152,82 +412,209 @@ fn place_building(
     mut next_state: ResMut<NextState<GameState>>,
     arena_index: Res<ArenaIndex>,
     pointer_pos: Res<PointerPosition>,
-    mut evw_buildings_updated: EventWriter<BuildingsUpdatedEvent>,
+    game_assets: Res<GameAssets>,
+    q_tower: Query<&Tower>,
+    q_placeholder: Query<Entity, With<BuildingPlaceholder>>,
+    mut evw_redraw_placement: EventWriter<RedrawPlacementEvent>,
 ) {
-    if arena_index.is_occupied(&pointer_pos.hex) {
-        return;
-    }
-
     let Some(selected_tower) = settings.selected_tower else {
+        let Some(from) = settings.moving_tower else {
+            // Choose the tower to move, its new placement is previewed until the next click
+            let Some(tower) = arena_index
+                .tower_index
+                .get(&pointer_pos.hex)
+                .and_then(|id| q_tower.get(*id).ok())
+            else {
+                return;
+            };
+
+            if let Ok(placeholder_id) = q_placeholder.single() {
+                let material = game_assets.tower_placeholder_materials.get(&tower.id);
+                commands
+                    .entity(placeholder_id)
+                    .insert(MeshMaterial3d(material));
+            }
+
+            settings.moving_tower = Some(pointer_pos.hex);
+            settings.rotation = tower.rotation;
+            evw_redraw_placement.write(RedrawPlacementEvent);
+            return;
+        };
+
+        if pointer_pos.hex == from {
+            // Clicking the tower again picks it up, it can be kept for later
+            settings.moving_tower = None;
+            commands.queue(PickUpTowerCommand { hex: from });
+            evw_redraw_placement.write(RedrawPlacementEvent);
+            return;
+        }
+
+        let Some(moving) = arena_index
+            .tower_index
+            .get(&from)
+            .and_then(|id| q_tower.get(*id).ok())
+        else {
+            // The tower was destroyed while it was being moved
+            settings.moving_tower = None;
+            return;
+        };
+        if !can_build_on(
+            &arena_index,
+            &q_tower,
+            pointer_pos.hex,
+            &moving.id,
+            moving.level,
+        ) {
+            return;
+        }
+
+        // Picking the tower up puts it at the front of the collection
+        commands.queue(PickUpTowerCommand { hex: from });
+        commands.queue(BuildTowerCommand {
+            index: 0,
+            hex: pointer_pos.hex,
+            rotation: settings.rotation,
+        });
+        settings.moving_tower = None;
+        next_state.set(GameState::Running);
         return;
     };
 
-    let Some(tower_kind) = settings.towers.get(selected_tower) else {
+    let Some(collected) = settings.towers.get(selected_tower) else {
         return;
     };
 
-    commands.queue(PlaceTowerCommand {
+    if !can_build_on(
+        &arena_index,
+        &q_tower,
+        pointer_pos.hex,
+        &collected.id,
+        collected.level,
+    ) {
+        return;
+    }
+
+    commands.queue(BuildTowerCommand {
+        index: selected_tower,
         hex: pointer_pos.hex,
-        tower: Tower {
-            kind: tower_kind.clone(),
-            rotation: 0,
-        },
+        rotation: settings.rotation,
     });
 
-    // Remove the tower from the player's collection.
-    settings.towers.remove(selected_tower);
-    settings.selected_tower = None;
-
-    evw_buildings_updated.write(BuildingsUpdatedEvent);
-
     // Return to playing the game
     next_state.set(GameState::Running);
 }
 
+/// Occupied hexes can only be built on to upgrade an identical tower.
+fn can_build_on(
+    arena_index: &ArenaIndex,
+    q_tower: &Query<&Tower>,
+    hex: Hex,
+    id: &TowerId,
+    level: u8,
+) -> bool {
+    !arena_index.is_occupied(&hex)
+        || arena_index
+            .tower_index
+            .get(&hex)
+            .and_then(|entity| q_tower.get(*entity).ok())
+            .is_some_and(|tower| tower.can_merge(id, level))
+}
+
 fn select_building(
     mut commands: Commands,
     state: Res<State<GameState>>,
     mut next_state: ResMut<NextState<GameState>>,
     mut settings: ResMut<BuildingSettings>,
-    key_input: Res<ButtonInput<KeyCode>>,
+    actions: Actions,
     game_assets: Res<GameAssets>,
     q_placeholder: Query<Entity, With<BuildingPlaceholder>>,
     mut evw_redraw_placement: EventWriter<RedrawPlacementEvent>,
 ) {
-    let key_codes = [
-        KeyCode::Digit1,
-        KeyCode::Digit2,
-        KeyCode::Digit3,
-        KeyCode::Digit4,
-        KeyCode::Digit5,
-    ];
-
-    for (index, key_code) in key_codes.iter().enumerate() {
-        if !key_input.just_pressed(*key_code) {
-            continue;
-        }
+    // Cycling wraps around the towers shown in the hotbar
+    let slots = settings.towers.len().min(InputAction::HOTBAR.len());
+    let index = if let Some(index) = InputAction::HOTBAR
+        .iter()
+        .position(|action| actions.just_pressed(*action))
+    {
+        index
+    } else if slots > 0 && actions.just_pressed(InputAction::HotbarNext) {
+        settings
+            .selected_tower
+            .map_or(0, |index| (index + 1) % slots)
+    } else if slots > 0 && actions.just_pressed(InputAction::HotbarPrevious) {
+        settings
+            .selected_tower
+            .map_or(slots - 1, |index| (index + slots - 1) % slots)
+    } else {
+        return;
+    };
 
-        evw_redraw_placement.write(RedrawPlacementEvent);
+    evw_redraw_placement.write(RedrawPlacementEvent);
 
-        let Some(kind) = settings.towers.get(index) else {
-            continue;
-        };
+    let Some(tower) = settings.towers.get(index) else {
+        return;
+    };
 
-        if let Ok(placeholder_id) = q_placeholder.single() {
-            let material = game_assets.tower_placeholder_materials.get(kind);
-            commands
-                .entity(placeholder_id)
-                .insert(MeshMaterial3d(material));
-        }
+    if let Ok(placeholder_id) = q_placeholder.single() {
+        let material = game_assets.tower_placeholder_materials.get(&tower.id);
+        commands
+            .entity(placeholder_id)
+            .insert(MeshMaterial3d(material));
+    }
 
-        settings.selected_tower = Some(index);
+    settings.selected_tower = Some(index);
+    settings.moving_tower = None;
 
-        if *state.get() != GameState::Building {
-            next_state.set(GameState::Building);
-        }
+    if *state.get() != GameState::Building {
+        next_state.set(GameState::Building);
+    }
+}
+
+/// Starts building without a selected tower, so a placed tower can be picked up.
+fn start_pick_up(
+    mut commands: Commands,
+    state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut settings: ResMut<BuildingSettings>,
+    game_assets: Res<GameAssets>,
+    q_placeholder: Query<Entity, With<BuildingPlaceholder>>,
+    mut evw_redraw_placement: EventWriter<RedrawPlacementEvent>,
+) {
+    settings.selected_tower = None;
+    settings.moving_tower = None;
+
+    if let Ok(placeholder_id) = q_placeholder.single() {
+        commands.entity(placeholder_id).insert(MeshMaterial3d(
+            game_assets.tower_placeholder_empty_material.clone(),
+        ));
+    }
 
-        break;
+    if *state.get() != GameState::Building {
+        next_state.set(GameState::Building);
     }
+
+    evw_redraw_placement.write(RedrawPlacementEvent);
+}
+
+fn rotate_building(
+    actions: Actions,
+    mut evr_mouse_wheel: EventReader<MouseWheel>,
+    mut settings: ResMut<BuildingSettings>,
+    mut evw_redraw_placement: EventWriter<RedrawPlacementEvent>,
+) {
+    let scroll: f32 = evr_mouse_wheel.read().map(|event| event.y).sum();
+
+    // Rotations are clockwise steps, so 5 steps is one step anticlockwise
+    let steps = if actions.just_pressed(InputAction::RotateTowerLeft) || scroll > 0.0 {
+        5
+    } else if actions.just_pressed(InputAction::RotateTowerRight) || scroll < 0.0 {
+        1
+    } else {
+        return;
+    };
+
+    settings.rotation = (settings.rotation + steps) % 6;
+    evw_redraw_placement.write(RedrawPlacementEvent);
 }
 
 fn redraw_placement(
@@ -236,6 +623,10 @@ fn redraw_placement(
     arena: Res<Arena>,
     pointer_pos: Res<PointerPosition>,
     settings: Res<BuildingSettings>,
+    tower_registry: Res<TowerRegistry>,
+    tower_lists: Res<Assets<TowerList>>,
+    arena_index: Res<ArenaIndex>,
+    q_tower: Query<&Tower>,
     mut graphic_transform: Single<&mut Transform, With<BuildingPlaceholder>>,
     q_highlight: Query<Entity, With<HighlightedHex>>,
 ) {
@@ -246,27 +637,66 @@ fn redraw_placement(
 
     let world_pos = arena.layout.hex_to_world_pos(pointer_pos.hex);
     graphic_transform.translation = Vec3::new(world_pos.x, PLACEHOLDER_HEIGHT, world_pos.y);
-
-    let Some(kind) = settings.get_selected() else {
+    graphic_transform.rotation = Tower::mesh_rotation(settings.rotation);
+
+    // Preview the tower being moved like one from the collection
+    let moving = settings
+        .moving_tower
+        .and_then(|hex| arena_index.tower_index.get(&hex))
+        .and_then(|id| q_tower.get(*id).ok())
+        .map(|tower| CollectedTower {
+            id: tower.id.clone(),
+            level: tower.level,
+            wear: None,
+        });
+    let Some(selected) = settings.get_selected().or(moving) else {
+        return;
+    };
+    let Some(definition) = tower_registry.get(&tower_lists, &selected.id) else {
         return;
     };
 
-    for action in kind.actions() {
+    // Hovering an identical tower previews it upgraded, with its own rotation
+    let merge_target = arena_index
+        .tower_index
+        .get(&pointer_pos.hex)
+        .filter(|_| settings.moving_tower != Some(pointer_pos.hex))
+        .and_then(|id| q_tower.get(*id).ok())
+        .filter(|tower| tower.can_merge(&selected.id, selected.level));
+    let (level, rotation) = match merge_target {
+        Some(tower) => (tower.level + 1, tower.rotation),
+        None => (selected.level, settings.rotation),
+    };
+    graphic_transform.rotation = Tower::mesh_rotation(rotation);
+
+    for action in definition.actions_at_level(level) {
         match action {
-            TowerAction::Shoot(direction) => {
-                let mut hex = pointer_pos.hex + direction;
-                while hex.unsigned_distance_to(Hex::ZERO) <= Arena::RADIUS {
+            TowerAction::Shoot { direction, .. } | TowerAction::Beam { direction, .. } => {
+                let direction = EdgeDirection::from(direction) >> rotation;
+                for hex in Arena::line_to_edge(pointer_pos.hex, direction) {
                     commands.spawn(highlighted_hex_bundle(hex, &arena, &game_assets));
-                    hex += direction;
                 }
             }
-            TowerAction::Explode(range) => {
+            TowerAction::Explode { range, .. }
+            | TowerAction::Lightning { range, .. }
+            | TowerAction::GravityWell { range, .. } => {
                 for hex in pointer_pos.hex.range(range) {
                     if hex.unsigned_distance_to(Hex::ZERO) <= Arena::RADIUS {
                         commands.spawn(highlighted_hex_bundle(hex, &arena, &game_assets));
                     }
                 }
             }
+            // Delays the other actions, there's no area to show
+            TowerAction::Fuse { .. } => {}
+            // Shows the mirror, it runs along the axis both ways
+            TowerAction::Reflect { axis } => {
+                let axis = EdgeDirection::from(axis) >> rotation;
+                for hex in Arena::line_to_edge(pointer_pos.hex, axis)
+                    .chain(Arena::line_to_edge(pointer_pos.hex, -axis))
+                {
+                    commands.spawn(highlighted_hex_bundle(hex, &arena, &game_assets));
+                }
+            }
         }
     }
 }
@@ -0,0 +1,254 @@
+use bevy::prelude::*;
+use std::time::Duration;
+
+use crate::{
+    AppState, EnemyTeam, GameState, PlayerTeam, Team,
+    arena_index::{ArenaHex, ArenaIndex, OutOfBoundsEvent},
+    game_assets::GameAssets,
+    health::{DamageEvent, Health},
+    interpolation::InterpolateTranslation,
+    tower::{Tower, TowerList, TowerRegistry, TriggerTowerEvent, bullet_rotation},
+};
+
+/// How fast bullets from the player's gun and towers travel.
+pub const BULLET_SPEED: f32 = 30.0;
+/// How long bullets from the player's gun last, towers can shoot bullets that last longer.
+pub const BULLET_LIFETIME: Duration = Duration::from_millis(500);
+const BULLET_HIT_RADIUS: f32 = 1.0;
+
+pub struct BulletPlugin;
+
+impl Plugin for BulletPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnExit(AppState::InGame), cleanup_bullets)
+            .add_systems(
+                FixedUpdate,
+                (
+                    update_bullets,
+                    (check_unit_collision, check_tower_collision).after(update_bullets),
+                )
+                    .in_set(BulletSet)
+                    .run_if(in_state(AppState::InGame))
+                    .run_if(in_state(GameState::Running)),
+            );
+    }
+}
+
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BulletSet;
+
+/// What a bullet does when it enters a tower's hex, mirrors reflect bullets that don't ignore
+/// towers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TowerHit {
+    /// Triggers the tower and is used up.
+    Trigger,
+    /// Damages the tower and is used up.
+    Damage,
+    /// Flies over the tower.
+    Ignore,
+}
+
+/// A projectile that damages the other team's units.
+#[derive(Component)]
+#[require(ArenaHex, InterpolateTranslation)]
+pub struct Bullet {
+    team: Team,
+    damage: u16,
+    speed: f32,
+    timer: Timer,
+    tower_hit: TowerHit,
+    /// A list of all towers that have been triggered in this event chain.
+    trigger_history: Vec<Entity>,
+    /// The tower that shot the bullet, bullets start inside it so it's skipped until the bullet
+    /// leaves its first hex.
+    fired_from: Option<Entity>,
+}
+
+pub struct SpawnBulletCommand {
+    pub team: Team,
+    pub transform: Transform,
+    pub damage: u16,
+    pub speed: f32,
+    /// How long the bullet travels before it's removed.
+    pub lifetime: Duration,
+    pub tower_hit: TowerHit,
+    pub trigger_history: Vec<Entity>,
+}
+
+impl Command for SpawnBulletCommand {
+    fn apply(self, world: &mut World) {
+        let mut transform = self.transform;
+        transform.translation.y = 0.5;
+
+        let visuals = world.get_resource::<GameAssets>().map(|game_assets| {
+            let material = match self.team {
+                Team::Player => game_assets.player_bullet_material.clone(),
+                Team::Enemy => game_assets.enemy_bullet_material.clone(),
+            };
+            (
+                Mesh3d(game_assets.bullet_mesh.clone()),
+                MeshMaterial3d(material),
+            )
+        });
+
+        let mut bullet = world.spawn((
+            Bullet {
+                team: self.team,
+                damage: self.damage,
+                speed: self.speed,
+                timer: Timer::new(self.lifetime, TimerMode::Once),
+                tower_hit: self.tower_hit,
+                fired_from: self.trigger_history.last().copied(),
+                trigger_history: self.trigger_history,
+            },
+            transform,
+        ));
+        bullet.observe(out_of_bounds_observer);
+
+        if let Some(visuals) = visuals {
+            bullet.insert(visuals);
+        }
+    }
+}
+
+fn cleanup_bullets(mut commands: Commands, q_bullets: Query<Entity, With<Bullet>>) {
+    for entity in q_bullets {
+        commands.entity(entity).try_despawn();
+    }
+}
+
+fn update_bullets(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut q_bullets: Query<(Entity, &mut Bullet, &mut Transform)>,
+) {
+    for (entity, mut bullet, mut transform) in q_bullets.iter_mut() {
+        // Tick timer, if it's finished despawn and skip
+        bullet.timer.tick(time.delta());
+        if bullet.timer.finished() {
+            commands.entity(entity).try_despawn();
+            continue;
+        }
+
+        transform.translation =
+            transform.translation + transform.forward() * bullet.speed * time.delta_secs();
+    }
+}
+
+fn check_unit_collision(
+    mut commands: Commands,
+    mut evw_damage: EventWriter<DamageEvent>,
+    q_bullet: Query<(Entity, &Bullet, &Transform)>,
+    q_units: Query<
+        (Entity, &Transform, Has<EnemyTeam>),
+        (
+            Or<(With<PlayerTeam>, With<EnemyTeam>)>,
+            With<Health>,
+            Without<Bullet>,
+        ),
+    >,
+) {
+    for (bullet_entity, bullet, bullet_trans) in q_bullet {
+        for (unit_entity, unit_trans, is_enemy) in q_units {
+            // Bullets only hit the other team
+            if is_enemy != (bullet.team == Team::Player) {
+                continue;
+            }
+
+            if bullet_trans
+                .translation
+                .xz()
+                .distance(unit_trans.translation.xz())
+                < BULLET_HIT_RADIUS
+            {
+                evw_damage.write(DamageEvent {
+                    target: unit_entity,
+                    damage: bullet.damage,
+                    chain_length: bullet.trigger_history.len(),
+                });
+                commands.entity(bullet_entity).try_despawn();
+                break;
+            }
+        }
+    }
+}
+
+fn check_tower_collision(
+    mut commands: Commands,
+    arena_index: Res<ArenaIndex>,
+    tower_registry: Res<TowerRegistry>,
+    tower_lists: Res<Assets<TowerList>>,
+    mut evw_trigger_tower: EventWriter<TriggerTowerEvent>,
+    mut evw_damage: EventWriter<DamageEvent>,
+    mut q_bullet: Query<(Entity, &mut Bullet, &mut Transform, &ArenaHex), Changed<ArenaHex>>,
+    q_tower: Query<(&Tower, &Transform), Without<Bullet>>,
+) {
+    for (bullet_id, mut bullet, mut transform, arena_hex) in q_bullet.iter_mut() {
+        let fired_from = bullet.fired_from.take();
+        if bullet.tower_hit == TowerHit::Ignore {
+            continue;
+        }
+
+        let Some(tower_id) = arena_index.tower_index.get(&arena_hex.hex) else {
+            // There is no tower in this hex
+            continue;
+        };
+
+        if fired_from == Some(*tower_id) {
+            // Still leaving the tower that shot it
+            continue;
+        }
+
+        let tower = q_tower
+            .get(*tower_id)
+            .ok()
+            .and_then(|(tower, tower_transform)| {
+                let definition = tower_registry.get(&tower_lists, &tower.id)?;
+                Some((tower, tower_transform, definition))
+            });
+        if let Some((tower, tower_transform, definition)) = tower {
+            if let Some(axis) = definition.reflect_axis() {
+                // Re-emit the bullet from the mirror, it keeps its history, damage and lifetime
+                let direction = tower.reflect(axis, *transform.forward());
+                transform.translation.x = tower_transform.translation.x;
+                transform.translation.z = tower_transform.translation.z;
+                transform.rotation = bullet_rotation(direction);
+                continue;
+            }
+
+            if bullet.tower_hit == TowerHit::Trigger
+                && !definition.can_trigger_in_chain(*tower_id, &bullet.trigger_history)
+            {
+                // Used up in this chain, the bullet passes through
+                continue;
+            }
+        }
+
+        match bullet.tower_hit {
+            TowerHit::Trigger => {
+                evw_trigger_tower.write(TriggerTowerEvent {
+                    target: *tower_id,
+                    trigger_history: bullet.trigger_history.clone(),
+                });
+                bullet.trigger_history.push(*tower_id);
+            }
+            TowerHit::Damage => {
+                evw_damage.write(DamageEvent {
+                    target: *tower_id,
+                    damage: bullet.damage,
+                    chain_length: 0,
+                });
+            }
+            // Bullets that ignore towers were skipped above
+            TowerHit::Ignore => continue,
+        }
+
+        commands.entity(bullet_id).try_despawn();
+    }
+}
+
+fn out_of_bounds_observer(trigger: Trigger<OutOfBoundsEvent>, mut commands: Commands) {
+    // Despawn bullet when it goes out of arena bounds
+    commands.entity(trigger.target()).try_despawn();
+}
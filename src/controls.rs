@@ -0,0 +1,303 @@
+use std::collections::BTreeMap;
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    AppState,
+    persistence::{load_ron, save_ron},
+};
+
+const CONTROLS_PATH: &str = "controls.ron";
+/// Stick positions closer to the centre than this are ignored.
+const STICK_DEADZONE: f32 = 0.2;
+
+/// Maps physical inputs to `InputAction`s, read them with `Actions` instead of `ButtonInput`.
+pub struct ControlsPlugin {
+    /// Whether bindings are loaded from and saved to the controls file, otherwise the defaults
+    /// are used.
+    pub persist: bool,
+}
+
+impl Plugin for ControlsPlugin {
+    fn build(&self, app: &mut App) {
+        let bindings = if self.persist {
+            load_bindings()
+        } else {
+            ControlBindings::default()
+        };
+        app.insert_resource(bindings);
+
+        if self.persist {
+            app.add_systems(OnExit(AppState::Settings), save_bindings);
+        }
+    }
+}
+
+/// Everything the player can do with a button.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum InputAction {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    Fire,
+    /// Builds the selected tower.
+    Place,
+    /// Stops building.
+    Cancel,
+    /// Rotates the tower being built anticlockwise.
+    RotateTowerLeft,
+    /// Rotates the tower being built clockwise.
+    #[serde(alias = "RotateTower")]
+    RotateTowerRight,
+    /// Picks up a placed tower so it can be moved or kept for later.
+    PickUp,
+    Pause,
+    /// Selects the next tower in the hotbar.
+    HotbarNext,
+    /// Selects the previous tower in the hotbar.
+    HotbarPrevious,
+    Hotbar1,
+    Hotbar2,
+    Hotbar3,
+    Hotbar4,
+    Hotbar5,
+}
+
+impl InputAction {
+    pub const ALL: [InputAction; 18] = [
+        InputAction::MoveUp,
+        InputAction::MoveDown,
+        InputAction::MoveLeft,
+        InputAction::MoveRight,
+        InputAction::Fire,
+        InputAction::Place,
+        InputAction::Cancel,
+        InputAction::RotateTowerLeft,
+        InputAction::RotateTowerRight,
+        InputAction::PickUp,
+        InputAction::Pause,
+        InputAction::HotbarNext,
+        InputAction::HotbarPrevious,
+        InputAction::Hotbar1,
+        InputAction::Hotbar2,
+        InputAction::Hotbar3,
+        InputAction::Hotbar4,
+        InputAction::Hotbar5,
+    ];
+
+    /// Selects the tower in each hotbar slot.
+    pub const HOTBAR: [InputAction; 5] = [
+        InputAction::Hotbar1,
+        InputAction::Hotbar2,
+        InputAction::Hotbar3,
+        InputAction::Hotbar4,
+        InputAction::Hotbar5,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            InputAction::MoveUp => "Move up",
+            InputAction::MoveDown => "Move down",
+            InputAction::MoveLeft => "Move left",
+            InputAction::MoveRight => "Move right",
+            InputAction::Fire => "Fire",
+            InputAction::Place => "Place tower",
+            InputAction::Cancel => "Cancel building",
+            InputAction::RotateTowerLeft => "Rotate tower left",
+            InputAction::RotateTowerRight => "Rotate tower right",
+            InputAction::PickUp => "Pick up tower",
+            InputAction::Pause => "Pause",
+            InputAction::HotbarNext => "Next tower",
+            InputAction::HotbarPrevious => "Previous tower",
+            InputAction::Hotbar1 => "Hotbar 1",
+            InputAction::Hotbar2 => "Hotbar 2",
+            InputAction::Hotbar3 => "Hotbar 3",
+            InputAction::Hotbar4 => "Hotbar 4",
+            InputAction::Hotbar5 => "Hotbar 5",
+        }
+    }
+
+    fn default_bindings(&self) -> Vec<Binding> {
+        match self {
+            // Movement also always follows the left stick
+            InputAction::MoveUp => vec![Binding::Key(KeyCode::KeyW)],
+            InputAction::MoveDown => vec![Binding::Key(KeyCode::KeyS)],
+            InputAction::MoveLeft => vec![Binding::Key(KeyCode::KeyA)],
+            InputAction::MoveRight => vec![Binding::Key(KeyCode::KeyD)],
+            InputAction::Fire => vec![
+                Binding::Mouse(MouseButton::Left),
+                Binding::Gamepad(GamepadButton::RightTrigger2),
+            ],
+            InputAction::Place => vec![
+                Binding::Mouse(MouseButton::Left),
+                Binding::Gamepad(GamepadButton::South),
+            ],
+            InputAction::Cancel => vec![
+                Binding::Key(KeyCode::Escape),
+                Binding::Gamepad(GamepadButton::East),
+            ],
+            // The mouse wheel also rotates towers
+            InputAction::RotateTowerLeft => vec![
+                Binding::Key(KeyCode::KeyQ),
+                Binding::Gamepad(GamepadButton::West),
+            ],
+            InputAction::RotateTowerRight => vec![
+                Binding::Key(KeyCode::KeyE),
+                Binding::Gamepad(GamepadButton::North),
+            ],
+            InputAction::PickUp => vec![
+                Binding::Key(KeyCode::KeyF),
+                Binding::Gamepad(GamepadButton::DPadDown),
+            ],
+            InputAction::Pause => vec![
+                Binding::Key(KeyCode::Escape),
+                Binding::Gamepad(GamepadButton::Start),
+            ],
+            InputAction::HotbarNext => vec![Binding::Gamepad(GamepadButton::RightTrigger)],
+            InputAction::HotbarPrevious => vec![Binding::Gamepad(GamepadButton::LeftTrigger)],
+            InputAction::Hotbar1 => vec![Binding::Key(KeyCode::Digit1)],
+            InputAction::Hotbar2 => vec![Binding::Key(KeyCode::Digit2)],
+            InputAction::Hotbar3 => vec![Binding::Key(KeyCode::Digit3)],
+            InputAction::Hotbar4 => vec![Binding::Key(KeyCode::Digit4)],
+            InputAction::Hotbar5 => vec![Binding::Key(KeyCode::Digit5)],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Binding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    /// A button on any connected gamepad.
+    Gamepad(GamepadButton),
+}
+
+impl Binding {
+    pub fn is_gamepad(&self) -> bool {
+        matches!(self, Binding::Gamepad(_))
+    }
+
+    /// A short name to show the player, e.g. `W` or `Mouse Left`.
+    pub fn display(&self) -> String {
+        match self {
+            Binding::Key(key_code) => {
+                let name = format!("{key_code:?}");
+                name.strip_prefix("Key")
+                    .or_else(|| name.strip_prefix("Digit"))
+                    .unwrap_or(&name)
+                    .to_string()
+            }
+            Binding::Mouse(mouse_button) => format!("Mouse {mouse_button:?}"),
+            Binding::Gamepad(gamepad_button) => format!("Pad {gamepad_button:?}"),
+        }
+    }
+}
+
+/// The bindings for each action, saved to the controls file so players can also edit it by hand.
+#[derive(Resource, Serialize, Deserialize, Debug, Clone)]
+pub struct ControlBindings {
+    bindings: BTreeMap<InputAction, Vec<Binding>>,
+}
+
+impl Default for ControlBindings {
+    fn default() -> Self {
+        Self {
+            bindings: InputAction::ALL
+                .iter()
+                .map(|action| (*action, action.default_bindings()))
+                .collect(),
+        }
+    }
+}
+
+impl ControlBindings {
+    pub fn get(&self, action: InputAction) -> &[Binding] {
+        self.bindings
+            .get(&action)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Replaces an action's binding for the same kind of device (keyboard and mouse, or gamepad)
+    /// with `binding`.
+    pub fn set(&mut self, action: InputAction, binding: Binding) {
+        let bindings = self.bindings.entry(action).or_default();
+        bindings.retain(|existing| existing.is_gamepad() != binding.is_gamepad());
+        bindings.push(binding);
+    }
+}
+
+/// Reads the state of `InputAction`s through the player's bindings, and the gamepad sticks.
+#[derive(SystemParam)]
+pub struct Actions<'w, 's> {
+    bindings: Res<'w, ControlBindings>,
+    key_input: Res<'w, ButtonInput<KeyCode>>,
+    mouse_input: Res<'w, ButtonInput<MouseButton>>,
+    gamepads: Query<'w, 's, &'static Gamepad>,
+}
+
+impl Actions<'_, '_> {
+    pub fn pressed(&self, action: InputAction) -> bool {
+        self.bindings
+            .get(action)
+            .iter()
+            .any(|binding| match binding {
+                Binding::Key(key_code) => self.key_input.pressed(*key_code),
+                Binding::Mouse(mouse_button) => self.mouse_input.pressed(*mouse_button),
+                Binding::Gamepad(gamepad_button) => self
+                    .gamepads
+                    .iter()
+                    .any(|gamepad| gamepad.pressed(*gamepad_button)),
+            })
+    }
+
+    pub fn just_pressed(&self, action: InputAction) -> bool {
+        self.bindings
+            .get(action)
+            .iter()
+            .any(|binding| match binding {
+                Binding::Key(key_code) => self.key_input.just_pressed(*key_code),
+                Binding::Mouse(mouse_button) => self.mouse_input.just_pressed(*mouse_button),
+                Binding::Gamepad(gamepad_button) => self
+                    .gamepads
+                    .iter()
+                    .any(|gamepad| gamepad.just_pressed(*gamepad_button)),
+            })
+    }
+
+    /// The left stick of the first gamepad that's using it, x is right and y is up.
+    pub fn left_stick(&self) -> Option<Vec2> {
+        self.gamepads
+            .iter()
+            .map(Gamepad::left_stick)
+            .find(|stick| stick.length() > STICK_DEADZONE)
+    }
+
+    /// The right stick of the first gamepad that's using it, x is right and y is up.
+    pub fn right_stick(&self) -> Option<Vec2> {
+        self.gamepads
+            .iter()
+            .map(Gamepad::right_stick)
+            .find(|stick| stick.length() > STICK_DEADZONE)
+    }
+}
+
+/// Run condition that's true when an action was just pressed, see `input_just_pressed`.
+pub fn action_just_pressed(action: InputAction) -> impl FnMut(Actions) -> bool + Clone {
+    move |actions: Actions| actions.just_pressed(action)
+}
+
+fn load_bindings() -> ControlBindings {
+    let mut bindings: ControlBindings = load_ron(CONTROLS_PATH, "controls");
+    // Actions missing from the file (e.g. added since it was saved) use their defaults
+    for (action, default_bindings) in ControlBindings::default().bindings {
+        bindings.bindings.entry(action).or_insert(default_bindings);
+    }
+    bindings
+}
+
+fn save_bindings(bindings: Res<ControlBindings>) {
+    save_ron(CONTROLS_PATH, "controls", &*bindings);
+}
@@ -0,0 +1,92 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+pub struct DifficultyPlugin;
+
+impl Plugin for DifficultyPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Difficulty>();
+    }
+}
+
+/// The difficulty preset chosen in the main menu, it applies to every run until it's changed.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+    Nightmare,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 4] = [
+        Difficulty::Easy,
+        Difficulty::Normal,
+        Difficulty::Hard,
+        Difficulty::Nightmare,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Normal => "Normal",
+            Difficulty::Hard => "Hard",
+            Difficulty::Nightmare => "Nightmare",
+        }
+    }
+
+    /// The next harder difficulty, wrapping around to `Easy`.
+    pub fn next(&self) -> Difficulty {
+        let index = Self::ALL.iter().position(|d| d == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    /// Scales the number of enemies in a `WaveStage`, also used for `remaining_threshold`.
+    pub fn scale_enemy_count(&self, count: usize) -> usize {
+        let multiplier = match self {
+            Difficulty::Easy => 0.75,
+            Difficulty::Normal => 1.0,
+            Difficulty::Hard => 1.25,
+            Difficulty::Nightmare => 1.5,
+        };
+        (count as f32 * multiplier).round() as usize
+    }
+
+    pub fn enemy_speed_multiplier(&self) -> f32 {
+        match self {
+            Difficulty::Easy => 0.8,
+            Difficulty::Normal => 1.0,
+            Difficulty::Hard => 1.15,
+            Difficulty::Nightmare => 1.3,
+        }
+    }
+
+    pub fn enemy_health(&self) -> u16 {
+        match self {
+            Difficulty::Easy | Difficulty::Normal => 1,
+            Difficulty::Hard => 2,
+            Difficulty::Nightmare => 3,
+        }
+    }
+
+    pub fn player_health(&self) -> u16 {
+        match self {
+            Difficulty::Easy => 5,
+            Difficulty::Normal => 3,
+            Difficulty::Hard => 2,
+            Difficulty::Nightmare => 1,
+        }
+    }
+
+    /// Scales score increases, rounding up so every kill is worth at least 1.
+    pub fn scale_score(&self, score: u128) -> u128 {
+        let percent = match self {
+            Difficulty::Easy => 50,
+            Difficulty::Normal => 100,
+            Difficulty::Hard => 150,
+            Difficulty::Nightmare => 200,
+        };
+        (score * percent).div_ceil(100)
+    }
+}
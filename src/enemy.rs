@@ -1,19 +1,41 @@
 use std::time::Duration;
 
 use bevy::prelude::*;
+use hexx::Hex;
+use serde::Deserialize;
 
 use crate::{
     AppState, EnemyTeam, GameState, Team,
-    arena_index::ArenaHex,
+    arena::Arena,
+    arena_index::{ArenaHex, ArenaIndex},
+    bullet::{SpawnBulletCommand, TowerHit},
+    difficulty::Difficulty,
     explosion::{CreateExplosionCommand, ExplosionDamageArea},
+    flow_field::FlowField,
+    force::Attractable,
     game_assets::GameAssets,
     health::{DamageEvent, DiedEvent, Health},
+    interpolation::InterpolateTranslation,
     player::Player,
     score::IncreaseScoreEvent,
 };
 
 const MOVE_SPEED: f32 = 5.0;
 const COLLISION_DISTANCE: f32 = 0.8;
+/// How long between the start of each of a charger's dashes.
+const CHARGE_INTERVAL: Duration = Duration::from_secs(2);
+/// How long a charger's dash lasts.
+const CHARGE_DURATION: Duration = Duration::from_millis(400);
+const CHARGE_SPEED_MULTIPLIER: f32 = 4.0;
+/// How far to each side of a splitter the two runners it splits into spawn.
+const SPLIT_DISTANCE: f32 = 0.5;
+/// How close to the player shooters walk before they stop and fire.
+const SHOOTER_RANGE: f32 = 10.0;
+/// How close to the player shooters let themselves get before backing away.
+const SHOOTER_MIN_RANGE: f32 = 6.0;
+const SHOOT_INTERVAL: Duration = Duration::from_millis(2500);
+const ENEMY_BULLET_SPEED: f32 = 12.0;
+const ENEMY_BULLET_LIFETIME: Duration = Duration::from_millis(1500);
 
 pub struct EnemyPlugin;
 
@@ -21,8 +43,8 @@ impl Plugin for EnemyPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(OnExit(AppState::InGame), cleanup_enemies)
             .add_systems(
-                Update,
-                follow_and_self_destruct
+                FixedUpdate,
+                (follow_and_self_destruct, fire_enemy_guns)
                     .in_set(EnemySet)
                     .run_if(in_state(AppState::InGame))
                     .run_if(in_state(GameState::Running)),
@@ -34,40 +56,153 @@ impl Plugin for EnemyPlugin {
 pub struct EnemySet;
 
 #[derive(Component)]
-#[require(EnemyTeam, ArenaHex, Transform, Visibility)]
-pub struct Enemy;
+#[require(
+    EnemyTeam,
+    ArenaHex,
+    Attractable,
+    InterpolateTranslation,
+    Transform,
+    Visibility
+)]
+pub struct Enemy {
+    pub archetype: EnemyArchetype,
+}
+
+/// The kinds of enemy, wave stages choose which ones they spawn.
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EnemyArchetype {
+    /// Walks at the player, around any towers in the way.
+    #[default]
+    Grunt,
+    /// A faster grunt.
+    Runner,
+    /// Slow, but takes several hits to kill.
+    Brute,
+    /// Splits into runners when it dies, unless it dies hitting the player or a tower.
+    Splitter,
+    /// Walks slowly between short, fast dashes.
+    Charger,
+    /// Keeps its distance and shoots at the player, its bullets damage towers in the way.
+    Shooter,
+}
+
+impl EnemyArchetype {
+    pub const ALL: [EnemyArchetype; 6] = [
+        EnemyArchetype::Grunt,
+        EnemyArchetype::Runner,
+        EnemyArchetype::Brute,
+        EnemyArchetype::Splitter,
+        EnemyArchetype::Charger,
+        EnemyArchetype::Shooter,
+    ];
+
+    /// The archetype's health, from the difficulty's base enemy health.
+    pub fn health(&self, base: u16) -> u16 {
+        match self {
+            EnemyArchetype::Brute => base * 3,
+            EnemyArchetype::Splitter => base * 2,
+            _ => base,
+        }
+    }
+
+    pub fn speed_multiplier(&self) -> f32 {
+        match self {
+            EnemyArchetype::Grunt | EnemyArchetype::Splitter => 1.0,
+            EnemyArchetype::Runner => 1.8,
+            EnemyArchetype::Brute => 0.6,
+            EnemyArchetype::Charger => 0.7,
+            EnemyArchetype::Shooter => 0.8,
+        }
+    }
+}
+
+/// Makes a charger dash towards the player at the start of every `CHARGE_INTERVAL`.
+#[derive(Component)]
+struct Charger {
+    timer: Timer,
+}
+
+impl Default for Charger {
+    fn default() -> Self {
+        Self {
+            timer: Timer::new(CHARGE_INTERVAL, TimerMode::Repeating),
+        }
+    }
+}
+
+impl Charger {
+    fn is_dashing(&self) -> bool {
+        self.timer.elapsed() < CHARGE_DURATION
+    }
+}
+
+/// Makes a shooter fire at the player every `SHOOT_INTERVAL` while it's in range.
+#[derive(Component)]
+struct EnemyGun {
+    timer: Timer,
+}
+
+impl Default for EnemyGun {
+    fn default() -> Self {
+        Self {
+            timer: Timer::new(SHOOT_INTERVAL, TimerMode::Repeating),
+        }
+    }
+}
+
+/// Added to enemies that destroyed themselves on the player or a tower.
+#[derive(Component)]
+struct SelfDestructed;
 
 pub struct SpawnEnemyCommand {
     pub position: Vec2,
+    pub archetype: EnemyArchetype,
 }
 
 impl SpawnEnemyCommand {
+    /// Spawns a `EnemyArchetype::Grunt`.
     pub fn new(position: Vec2) -> Self {
-        Self { position }
+        Self {
+            position,
+            archetype: EnemyArchetype::Grunt,
+        }
     }
 }
 
 impl Command for SpawnEnemyCommand {
     fn apply(self, world: &mut World) -> () {
-        let (mesh_handle, material_handle) = {
-            let Some(game_assets) = world.get_resource::<GameAssets>() else {
-                panic!("GameAssets not available during SpawnEnemiesCommand");
-            };
+        let health = self
+            .archetype
+            .health(world.resource::<Difficulty>().enemy_health());
+        let visuals = world.get_resource::<GameAssets>().map(|game_assets| {
+            let assets = game_assets.enemy(self.archetype);
             (
-                game_assets.enemy_mesh.clone(),
-                game_assets.enemy_material.clone(),
+                Mesh3d(assets.mesh.clone()),
+                MeshMaterial3d(assets.material.clone()),
             )
-        };
+        });
+
+        let mut enemy = world.spawn((
+            Enemy {
+                archetype: self.archetype,
+            },
+            Health::new(health),
+            Transform::from_xyz(self.position.x, 1.0, self.position.y),
+        ));
+        enemy.observe(despawn_on_death);
+        match self.archetype {
+            EnemyArchetype::Charger => {
+                enemy.insert(Charger::default());
+            }
+            EnemyArchetype::Shooter => {
+                enemy.insert(EnemyGun::default());
+            }
+            _ => {}
+        }
 
-        world
-            .spawn((
-                Enemy,
-                Health::new(1),
-                Transform::from_xyz(self.position.x, 1.0, self.position.y),
-                Mesh3d(mesh_handle.clone()),
-                MeshMaterial3d(material_handle.clone()),
-            ))
-            .observe(despawn_on_death);
+        if let Some(visuals) = visuals {
+            enemy.insert(visuals);
+        }
     }
 }
 
@@ -78,31 +213,123 @@ fn cleanup_enemies(mut commands: Commands, q_enemy: Query<Entity, With<Enemy>>)
 }
 
 fn follow_and_self_destruct(
+    mut commands: Commands,
     mut evw_damage: EventWriter<DamageEvent>,
     time: Res<Time>,
+    difficulty: Res<Difficulty>,
+    arena: Res<Arena>,
+    arena_index: Res<ArenaIndex>,
+    flow_field: Res<FlowField>,
     q_player: Query<(Entity, &Transform), (With<Player>, Without<Enemy>)>,
-    mut q_enemy: Query<(Entity, &mut Transform), With<Enemy>>,
+    mut q_enemy: Query<(
+        Entity,
+        &Enemy,
+        &mut Transform,
+        &ArenaHex,
+        Option<&mut Charger>,
+    )>,
 ) -> Result {
     let (player_entity, player_transform) = q_player.single()?;
     let player_pos = player_transform.translation;
 
-    for (enemy_entity, mut enemy_transform) in q_enemy.iter_mut() {
+    for (enemy_entity, enemy, mut enemy_transform, arena_hex, charger) in q_enemy.iter_mut() {
+        let mut speed =
+            MOVE_SPEED * difficulty.enemy_speed_multiplier() * enemy.archetype.speed_multiplier();
+        if let Some(mut charger) = charger {
+            charger.timer.tick(time.delta());
+            if charger.is_dashing() {
+                speed *= CHARGE_SPEED_MULTIPLIER;
+            }
+        }
+
         let y = enemy_transform.translation.y;
-        let direction = (player_pos.with_y(y) - enemy_transform.translation).normalize_or_zero();
-        enemy_transform.translation += direction * MOVE_SPEED * time.delta_secs();
-        enemy_transform.look_at(player_pos.with_y(y), Vec3::Y);
-        if player_pos.xz().distance(enemy_transform.translation.xz()) < COLLISION_DISTANCE {
-            evw_damage.write(DamageEvent {
-                target: player_entity,
-                damage: 1,
-                chain_length: 0,
-            });
-            evw_damage.write(DamageEvent {
-                target: enemy_entity,
-                damage: 100,
-                chain_length: 0,
-            });
+        let player_distance = player_pos.xz().distance(enemy_transform.translation.xz());
+        let is_shooter = enemy.archetype == EnemyArchetype::Shooter;
+        let in_range = is_shooter && player_distance <= SHOOTER_RANGE;
+        let too_close = is_shooter && player_distance < SHOOTER_MIN_RANGE;
+        // Walk straight at the player when nothing is in the way, otherwise follow the flow field
+        // around towers. Enemies that can't reach the player walk straight at them
+        let waypoint = match flow_field.next_hex(arena_hex.hex) {
+            Some(next) if !flow_field.has_clear_line(arena_hex.hex) => {
+                let next_pos = arena.layout.hex_to_world_pos(next);
+                Vec3::new(next_pos.x, y, next_pos.y)
+            }
+            _ => player_pos.with_y(y),
+        };
+        if too_close {
+            let away = (enemy_transform.translation - player_pos.with_y(y)).normalize_or_zero();
+            let next_pos = enemy_transform.translation + away * speed * time.delta_secs();
+            let next_hex = arena.layout.world_pos_to_hex(next_pos.xz());
+            // Cornered shooters hold their ground rather than leave the arena or hit a tower
+            if next_hex.unsigned_distance_to(Hex::ZERO) <= Arena::RADIUS
+                && !arena_index.tower_index.contains_key(&next_hex)
+            {
+                enemy_transform.translation = next_pos;
+            }
+            enemy_transform.look_at(player_pos.with_y(y), Vec3::Y);
+        } else if in_range {
+            enemy_transform.look_at(player_pos.with_y(y), Vec3::Y);
+        } else {
+            let direction = (waypoint - enemy_transform.translation).normalize_or_zero();
+            enemy_transform.translation += direction * speed * time.delta_secs();
+            if direction != Vec3::ZERO {
+                enemy_transform.look_to(direction, Vec3::Y);
+            }
         }
+        let hit_player =
+            player_pos.xz().distance(enemy_transform.translation.xz()) < COLLISION_DISTANCE;
+        let target = if hit_player {
+            player_entity
+        } else if let Some(tower_entity) = arena_index.tower_index.get(&arena_hex.hex) {
+            // Enemies destroy themselves on towers they walk into, like they do on the player
+            *tower_entity
+        } else {
+            continue;
+        };
+
+        evw_damage.write(DamageEvent {
+            target,
+            damage: 1,
+            chain_length: 0,
+        });
+        evw_damage.write(DamageEvent {
+            target: enemy_entity,
+            damage: 100,
+            chain_length: 0,
+        });
+        commands.entity(enemy_entity).insert(SelfDestructed);
+    }
+
+    Ok(())
+}
+
+fn fire_enemy_guns(
+    mut commands: Commands,
+    time: Res<Time>,
+    q_player: Query<&Transform, (With<Player>, Without<Enemy>)>,
+    mut q_guns: Query<(&mut EnemyGun, &Transform), With<Enemy>>,
+) -> Result {
+    let player_pos = q_player.single()?.translation;
+
+    for (mut gun, transform) in q_guns.iter_mut() {
+        gun.timer.tick(time.delta());
+        if !gun.timer.just_finished()
+            || player_pos.xz().distance(transform.translation.xz()) > SHOOTER_RANGE
+        {
+            continue;
+        }
+
+        let y = transform.translation.y;
+        commands.queue(SpawnBulletCommand {
+            team: Team::Enemy,
+            transform: Transform::from_translation(transform.translation)
+                .looking_at(player_pos.with_y(y), Vec3::Y),
+            damage: 1,
+            speed: ENEMY_BULLET_SPEED,
+            lifetime: ENEMY_BULLET_LIFETIME,
+            tower_hit: TowerHit::Damage,
+            trigger_history: Vec::new(),
+        });
     }
 
     Ok(())
@@ -112,27 +339,41 @@ fn despawn_on_death(
     trigger: Trigger<DiedEvent>,
     mut commands: Commands,
     mut evw_increase_score: EventWriter<IncreaseScoreEvent>,
-    q_transform: Query<&Transform>,
+    q_enemy: Query<(&Enemy, &Transform, Has<SelfDestructed>)>,
 ) {
     evw_increase_score.write(IncreaseScoreEvent {
         score: 1,
         chain_length: trigger.chain_length,
     });
 
-    if let Ok(transform) = q_transform.get(trigger.entity) {
-        commands.queue(CreateExplosionCommand {
-            team: Team::Enemy,
-            color: LinearRgba::new(1.0, 0.0, 0.0, 1.0),
-            duration: Duration::from_millis(600),
-            position: transform.translation.xz(),
-            damage: 1,
-            damage_area: ExplosionDamageArea::Radius(1.0),
-            damage_delay: Duration::from_millis(200),
-            radius: 5.0,
-            strength: 50.0,
-            strength_modifier: -100.0,
-            trigger_history: Vec::new(),
-        });
+    let Ok((enemy, transform, self_destructed)) = q_enemy.get(trigger.entity) else {
+        commands.entity(trigger.entity).try_despawn();
+        return;
+    };
+
+    commands.queue(CreateExplosionCommand {
+        team: Team::Enemy,
+        color: LinearRgba::new(1.0, 0.0, 0.0, 1.0),
+        duration: Duration::from_millis(600),
+        position: transform.translation.xz(),
+        damage: 1,
+        damage_area: ExplosionDamageArea::Radius(1.0),
+        damage_delay: Duration::from_millis(200),
+        radius: 5.0,
+        strength: 50.0,
+        strength_modifier: -100.0,
+        trigger_history: Vec::new(),
+    });
+
+    if enemy.archetype == EnemyArchetype::Splitter && !self_destructed {
+        // Split sideways so the runners don't overlap
+        let side = transform.right().xz() * SPLIT_DISTANCE;
+        for offset in [side, -side] {
+            commands.queue(SpawnEnemyCommand {
+                position: transform.translation.xz() + offset,
+                archetype: EnemyArchetype::Runner,
+            });
+        }
     }
 
     commands.entity(trigger.entity).try_despawn();
@@ -7,28 +7,39 @@ use crate::{
     arena::Arena,
     arena_index::ArenaIndex,
     force::ForceEmitter,
+    game_assets::GameAssets,
     health::{DamageEvent, Health},
     materials::ExplodingRingMaterial,
     tower::TriggerTowerEvent,
 };
 
+/// How far towers reach from the centre of their hex, explosions that reach them damage them.
+const TOWER_RADIUS: f32 = 0.8;
+
 pub struct ExplosionPlugin;
 
 impl Plugin for ExplosionPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(
+            FixedUpdate,
+            (update_explosion, apply_explosion_damage)
+                .in_set(ExplosionSet)
+                .run_if(in_state(AppState::InGame))
+                .run_if(in_state(GameState::Running)),
+        )
+        .add_systems(
             Update,
-            (
-                update_explosion,
-                update_material_times,
-                apply_explosion_damage,
-            )
+            update_material_times
+                .run_if(resource_exists::<Assets<ExplodingRingMaterial>>)
                 .run_if(in_state(AppState::InGame))
                 .run_if(in_state(GameState::Running)),
         );
     }
 }
 
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ExplosionSet;
+
 #[derive(Component, Debug)]
 #[require(ForceEmitter)]
 pub struct Explosion {
@@ -70,6 +81,30 @@ pub struct CreateExplosionCommand {
 
 impl Command for CreateExplosionCommand {
     fn apply(self, world: &mut World) -> () {
+        let id = world
+            .spawn((
+                Explosion {
+                    team: self.team,
+                    timer: Timer::new(self.duration, TimerMode::Once),
+                    damage_timer: Timer::new(self.damage_delay, TimerMode::Once),
+                    damage: self.damage,
+                    damage_area: self.damage_area,
+                    strength_modifier: self.strength_modifier,
+                    trigger_history: self.trigger_history,
+                },
+                ForceEmitter {
+                    strength: self.strength,
+                    radius: self.radius,
+                },
+                Transform::from_xyz(self.position.x, 0.5, self.position.y),
+            ))
+            .id();
+
+        // Visuals are only created when game assets are loaded (i.e. not when running headless)
+        if !world.contains_resource::<GameAssets>() {
+            return;
+        }
+
         let mesh_handle = {
             let mut meshes = world.get_resource_mut::<Assets<Mesh>>().unwrap();
             meshes.add(Plane3d::new(Vec3::Y, Vec2::splat(self.radius)))
@@ -86,24 +121,9 @@ impl Command for CreateExplosionCommand {
             })
         };
 
-        world.spawn((
-            Explosion {
-                team: self.team,
-                timer: Timer::new(self.duration, TimerMode::Once),
-                damage_timer: Timer::new(self.damage_delay, TimerMode::Once),
-                damage: self.damage,
-                damage_area: self.damage_area,
-                strength_modifier: self.strength_modifier,
-                trigger_history: self.trigger_history,
-            },
-            ForceEmitter {
-                strength: self.strength,
-                radius: self.radius,
-            },
-            Transform::from_xyz(self.position.x, 0.5, self.position.y),
-            Mesh3d(mesh_handle),
-            MeshMaterial3d(material_handle),
-        ));
+        world
+            .entity_mut(id)
+            .insert((Mesh3d(mesh_handle), MeshMaterial3d(material_handle)));
     }
 }
 
@@ -156,6 +176,21 @@ fn apply_explosion_damage(
                     target: *id,
                     trigger_history: explosion.trigger_history.clone(),
                 });
+
+                // Enemy explosions also damage the player's towers
+                if explosion.team == Team::Enemy {
+                    if let ExplosionDamageArea::Radius(radius) = explosion.damage_area {
+                        let tower_pos = arena.layout.hex_to_world_pos(hex);
+                        if transform.translation.xz().distance(tower_pos) > radius + TOWER_RADIUS {
+                            continue;
+                        }
+                    }
+                    evw_damage.write(DamageEvent {
+                        target: *id,
+                        damage: explosion.damage,
+                        chain_length: explosion.trigger_history.len(),
+                    });
+                }
             }
         }
 
@@ -0,0 +1,151 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use bevy::prelude::*;
+use hexx::Hex;
+
+use crate::{
+    AppState, GameState,
+    arena::ARENA_RADIUS,
+    arena_index::{ArenaHex, ArenaIndex},
+    player::Player,
+};
+
+pub struct FlowFieldPlugin;
+
+impl Plugin for FlowFieldPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FlowField>()
+            .add_systems(OnEnter(AppState::InGame), reset_flow_field)
+            .add_systems(
+                FixedUpdate,
+                update_flow_field
+                    .in_set(FlowFieldSet)
+                    .run_if(in_state(AppState::InGame))
+                    .run_if(in_state(GameState::Running)),
+            );
+    }
+}
+
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FlowFieldSet;
+
+/// How many steps each arena hex is from the player's hex, walking around towers. Enemies follow
+/// it downhill, it's rebuilt when the player changes hex or towers are placed or removed.
+#[derive(Resource, Default)]
+pub struct FlowField {
+    target: Option<Hex>,
+    /// The tower hexes the field was built around.
+    obstacles: HashSet<Hex>,
+    /// Hexes missing from this can't reach the target (or are obstacles).
+    distances: HashMap<Hex, u32>,
+}
+
+impl FlowField {
+    /// Builds the field over the arena, `target` is always reachable even if it's an obstacle.
+    pub fn new(target: Hex, obstacles: HashSet<Hex>) -> Self {
+        let mut distances = HashMap::from([(target, 0)]);
+        let mut queue = VecDeque::from([target]);
+        while let Some(hex) = queue.pop_front() {
+            let distance = distances[&hex] + 1;
+            for neighbor in hex.all_neighbors() {
+                if neighbor.unsigned_distance_to(Hex::ZERO) > ARENA_RADIUS
+                    || obstacles.contains(&neighbor)
+                    || distances.contains_key(&neighbor)
+                {
+                    continue;
+                }
+                distances.insert(neighbor, distance);
+                queue.push_back(neighbor);
+            }
+        }
+
+        Self {
+            target: Some(target),
+            obstacles,
+            distances,
+        }
+    }
+
+    pub fn distance(&self, hex: Hex) -> Option<u32> {
+        self.distances.get(&hex).copied()
+    }
+
+    /// The neighbor to walk to from `hex`, `None` at the target or if the target can't be
+    /// reached. Ties go to the neighbor closest to the target as the crow flies, so enemies don't
+    /// zig-zag.
+    pub fn next_hex(&self, hex: Hex) -> Option<Hex> {
+        let distance = self.distance(hex)?;
+        let target = self.target?;
+        hex.all_neighbors()
+            .into_iter()
+            .filter_map(|neighbor| Some((neighbor, self.distance(neighbor)?)))
+            .filter(|(_, neighbor_distance)| *neighbor_distance < distance)
+            .min_by_key(|(neighbor, neighbor_distance)| {
+                // Squared straight line distance in hex widths, the same for both orientations
+                let offset = *neighbor - target;
+                let straight = offset.x * offset.x + offset.y * offset.y + offset.x * offset.y;
+                (*neighbor_distance, straight)
+            })
+            .map(|(neighbor, _)| neighbor)
+    }
+
+    /// Whether the straight line from `hex` to the target doesn't cross any obstacles, enemies can
+    /// walk straight at the player instead of from hex to hex.
+    pub fn has_clear_line(&self, hex: Hex) -> bool {
+        let Some(target) = self.target else {
+            return false;
+        };
+        hex.line_to(target)
+            .all(|hex| hex == target || !self.obstacles.contains(&hex))
+    }
+
+    fn is_up_to_date(&self, target: Hex, tower_index: &HashMap<Hex, Entity>) -> bool {
+        self.target == Some(target)
+            && self.obstacles.len() == tower_index.len()
+            && tower_index.keys().all(|hex| self.obstacles.contains(hex))
+    }
+}
+
+fn reset_flow_field(mut flow_field: ResMut<FlowField>) {
+    *flow_field = FlowField::default();
+}
+
+fn update_flow_field(
+    arena_index: Res<ArenaIndex>,
+    mut flow_field: ResMut<FlowField>,
+    q_player: Query<&ArenaHex, With<Player>>,
+) -> Result {
+    let player_hex = q_player.single()?.hex;
+    if flow_field.is_up_to_date(player_hex, &arena_index.tower_index) {
+        return Ok(());
+    }
+
+    let obstacles = arena_index.tower_index.keys().copied().collect();
+    *flow_field = FlowField::new(player_hex, obstacles);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn routes_around_obstacles() {
+        // A wall between the target and (3, 0) with a gap at its end
+        let obstacles: HashSet<Hex> = (-2..=2).map(|r| Hex::new(2, r)).collect();
+        let field = FlowField::new(Hex::ZERO, obstacles);
+
+        assert_eq!(field.distance(Hex::new(2, 0)), None);
+        assert!(!field.has_clear_line(Hex::new(3, 0)));
+        assert!(field.distance(Hex::new(3, 0)).unwrap() > 3);
+
+        // Following the field reaches the target without crossing the wall
+        let mut hex = Hex::new(3, 0);
+        while let Some(next) = field.next_hex(hex) {
+            assert!(!field.obstacles.contains(&next));
+            hex = next;
+        }
+        assert_eq!(hex, Hex::ZERO);
+    }
+}
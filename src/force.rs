@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use bevy::prelude::*;
 
 use crate::{AppState, GameState};
@@ -8,8 +10,13 @@ impl Plugin for ForcePlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(OnExit(AppState::InGame), cleanup_emitters)
             .add_systems(
-                Update,
-                (apply_force, reduce_force)
+                FixedUpdate,
+                (
+                    apply_force,
+                    reduce_force,
+                    apply_attraction,
+                    update_attractors,
+                )
                     .in_set(ForceSet)
                     .run_if(in_state(AppState::InGame))
                     .run_if(in_state(GameState::Running)),
@@ -49,6 +56,29 @@ impl ForceEmitter {
     }
 }
 
+/// Turns a `ForceEmitter` into a temporary attractor that also pulls `Attractable` entities
+/// towards it, by `ForceEmitter::strength_at` units per second. Removed when `timer` finishes.
+#[derive(Component)]
+#[require(ForceEmitter)]
+pub struct Attractor {
+    pub timer: Timer,
+    /// Attractables are pulled no closer than this to the centre.
+    pub hold_distance: f32,
+}
+
+impl Attractor {
+    pub fn new(duration: Duration, hold_distance: f32) -> Self {
+        Self {
+            timer: Timer::new(duration, TimerMode::Once),
+            hold_distance,
+        }
+    }
+}
+
+/// Entities that are pulled by `Attractor`s.
+#[derive(Component, Default)]
+pub struct Attractable;
+
 /// Receives force from a `ForceEmitter`.
 #[derive(Component)]
 #[require(Force)]
@@ -124,6 +154,41 @@ fn reduce_force(
     }
 }
 
+fn apply_attraction(
+    time: Res<Time>,
+    q_attractors: Query<(&Attractor, &ForceEmitter, &Transform)>,
+    mut q_attractable: Query<&mut Transform, (With<Attractable>, Without<Attractor>)>,
+) {
+    for mut transform in q_attractable.iter_mut() {
+        for (attractor, emitter, emitter_transform) in q_attractors {
+            let offset = emitter_transform.translation.xz() - transform.translation.xz();
+            let distance = offset.length();
+            if distance > emitter.radius || distance <= attractor.hold_distance {
+                continue;
+            }
+
+            // Don't pull past the hold distance
+            let pull = (emitter.strength_at(distance) * time.delta_secs())
+                .min(distance - attractor.hold_distance);
+            let pull = offset.normalize_or_zero() * pull;
+            transform.translation += Vec3::new(pull.x, 0.0, pull.y);
+        }
+    }
+}
+
+fn update_attractors(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut q_attractors: Query<(Entity, &mut Attractor)>,
+) {
+    for (entity, mut attractor) in q_attractors.iter_mut() {
+        attractor.timer.tick(time.delta());
+        if attractor.timer.finished() {
+            commands.entity(entity).try_despawn();
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
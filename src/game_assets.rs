@@ -1,22 +1,25 @@
 use bevy::{
     asset::RenderAssetUsages,
     pbr::ExtendedMaterial,
+    platform::collections::HashMap,
     prelude::*,
     render::mesh::{Indices, PrimitiveTopology},
 };
 use hexx::{ColumnMeshBuilder, HexLayout, PlaneMeshBuilder};
 
 use crate::{
-    arena::Arena,
+    arena::{ARENA_COLUMN_HEIGHT, Arena, hex_column_mesh},
+    enemy::EnemyArchetype,
     materials::{BulletMaterial, TowerMaterial, TowerPlaceholderMaterial},
-    tower::TowerKind,
+    tower::{MAX_TOWER_LEVEL, TowerId, TowerList, TowerRegistry},
 };
 
 pub struct GameAssetPlugin;
 
 impl Plugin for GameAssetPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(PreStartup, load_assets);
+        app.add_systems(PreStartup, load_assets)
+            .add_systems(Update, update_tower_assets);
     }
 }
 
@@ -27,17 +30,28 @@ pub struct GameAssetSet;
 pub struct GameAssets {
     pub audiowide_font: Handle<Font>,
 
-    pub enemy_mesh: Handle<Mesh>,
-    pub enemy_material: Handle<StandardMaterial>,
+    pub arena_column_mesh: Handle<Mesh>,
+    pub arena_column_material: Handle<StandardMaterial>,
 
-    pub player_bullet_mesh: Handle<Mesh>,
+    pub player_mesh: Handle<Mesh>,
+    pub player_material: Handle<StandardMaterial>,
+
+    /// The mesh and material for each enemy archetype, use `GameAssets::enemy` to get them.
+    enemies: HashMap<EnemyArchetype, EnemyAssets>,
+
+    pub bullet_mesh: Handle<Mesh>,
     pub player_bullet_material: Handle<BulletMaterial>,
+    pub enemy_bullet_material: Handle<BulletMaterial>,
+
+    /// One unit long, stretched to the length of each beam.
+    pub beam_mesh: Handle<Mesh>,
 
     pub hex_plane_mesh: Handle<Mesh>,
     pub hex_plane_material: Handle<StandardMaterial>,
 
     pub tower_mesh: Handle<Mesh>,
-    pub tower_materials: TowerAssets<ExtendedMaterial<StandardMaterial, TowerMaterial>>,
+    /// The materials for each tower level, use `GameAssets::tower_material` to get one.
+    tower_materials: Vec<TowerAssets<ExtendedMaterial<StandardMaterial, TowerMaterial>>>,
 
     pub tower_placeholder_mesh: Handle<Mesh>,
     pub tower_placeholder_materials: TowerAssets<TowerPlaceholderMaterial>,
@@ -47,28 +61,66 @@ pub struct GameAssets {
     pub tower_empty_icon: Handle<Image>,
 }
 
+impl GameAssets {
+    /// The material for a placed tower, its highlights are colored by the tower's level.
+    pub fn tower_material(
+        &self,
+        id: &TowerId,
+        level: u8,
+    ) -> Handle<ExtendedMaterial<StandardMaterial, TowerMaterial>> {
+        let index = (level.clamp(1, MAX_TOWER_LEVEL) - 1) as usize;
+        self.tower_materials[index].get(id)
+    }
+
+    pub fn enemy(&self, archetype: EnemyArchetype) -> &EnemyAssets {
+        // Every archetype's assets are created in `load_assets`
+        &self.enemies[&archetype]
+    }
+}
+
+pub struct EnemyAssets {
+    pub mesh: Handle<Mesh>,
+    pub material: Handle<StandardMaterial>,
+}
+
+/// The color used to show a tower's level, on the tower itself and in the UI.
+pub fn tower_level_color(level: u8) -> LinearRgba {
+    match level {
+        0 | 1 => LinearRgba::new(0.2, 0.8, 0.2, 1.0),
+        2 => LinearRgba::new(0.2, 0.6, 1.0, 1.0),
+        _ => LinearRgba::new(1.0, 0.75, 0.1, 1.0),
+    }
+}
+
+/// The tint of a tower's icon, upgraded towers are tinted with their level's color.
+pub fn tower_icon_color(level: u8) -> Color {
+    if level > 1 {
+        tower_level_color(level).into()
+    } else {
+        Color::WHITE
+    }
+}
+
+/// Assets for each tower keyed by tower id, towers without assets use the fallback.
 pub struct TowerAssets<T: Asset> {
-    pub bullet2: Handle<T>,
-    pub bullet3: Handle<T>,
-    pub bullet4: Handle<T>,
-    pub bullet6: Handle<T>,
-    pub explosion1: Handle<T>,
-    pub explosion2: Handle<T>,
-    pub explosion3: Handle<T>,
+    handles: HashMap<TowerId, Handle<T>>,
+    fallback: Handle<T>,
 }
 
 impl<T: Asset> TowerAssets<T> {
-    pub fn get(&self, kind: &TowerKind) -> Handle<T> {
-        match *kind {
-            TowerKind::Bullet2 => self.bullet2.clone(),
-            TowerKind::Bullet3 => self.bullet3.clone(),
-            TowerKind::Bullet4 => self.bullet4.clone(),
-            TowerKind::Bullet6 => self.bullet6.clone(),
-            TowerKind::Explosion1 => self.explosion1.clone(),
-            TowerKind::Explosion2 => self.explosion2.clone(),
-            TowerKind::Explosion3 => self.explosion3.clone(),
+    fn new(fallback: Handle<T>) -> Self {
+        Self {
+            handles: HashMap::default(),
+            fallback,
         }
     }
+
+    pub fn get(&self, id: &TowerId) -> Handle<T> {
+        self.handles
+            .get(id)
+            .cloned()
+            .unwrap_or_else(|| self.fallback.clone())
+    }
 }
 
 fn load_assets(
@@ -82,18 +134,68 @@ fn load_assets(
     mut tower_placeholder_materials: ResMut<Assets<TowerPlaceholderMaterial>>,
 ) {
     let audiowide_font = asset_server.load("fonts/Audiowide-Regular.ttf");
-    let enemy_mesh = meshes.add(Cuboid::new(0.5, 0.3, 0.5));
-    let enemy_material = materials.add(StandardMaterial {
-        base_color: Color::hsl(350.0, 1.0, 0.5),
+
+    let arena_column_mesh = meshes.add(hex_column_mesh(&arena.layout, ARENA_COLUMN_HEIGHT));
+    let arena_column_material = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.0, 0.0, 0.0),
+        perceptual_roughness: 1.0,
+        ..default()
+    });
+
+    let player_mesh = meshes.add(Cylinder::new(0.5, 0.2));
+    let player_material = materials.add(StandardMaterial {
+        base_color: Color::hsl(100.0, 0.7, 0.3),
         perceptual_roughness: 1.0,
-        unlit: true,
         ..default()
     });
 
-    let player_bullet_mesh = meshes.add(Plane3d::new(Vec3::Y, Vec2::new(0.1, 1.0)));
+    let enemies = EnemyArchetype::ALL
+        .into_iter()
+        .map(|archetype| {
+            let (mesh, color): (Mesh, Color) = match archetype {
+                EnemyArchetype::Grunt => (
+                    Cuboid::new(0.5, 0.3, 0.5).into(),
+                    Color::hsl(350.0, 1.0, 0.5),
+                ),
+                EnemyArchetype::Runner => (
+                    Cuboid::new(0.35, 0.2, 0.6).into(),
+                    Color::hsl(25.0, 1.0, 0.5),
+                ),
+                EnemyArchetype::Brute => (
+                    Cuboid::new(0.8, 0.5, 0.8).into(),
+                    Color::hsl(340.0, 0.9, 0.3),
+                ),
+                EnemyArchetype::Splitter => (Sphere::new(0.35).into(), Color::hsl(300.0, 1.0, 0.5)),
+                EnemyArchetype::Charger => (
+                    Cuboid::new(0.4, 0.3, 0.8).into(),
+                    Color::hsl(50.0, 1.0, 0.5),
+                ),
+                EnemyArchetype::Shooter => {
+                    (Cylinder::new(0.3, 0.5).into(), Color::hsl(270.0, 1.0, 0.6))
+                }
+            };
+            let assets = EnemyAssets {
+                mesh: meshes.add(mesh),
+                material: materials.add(StandardMaterial {
+                    base_color: color,
+                    perceptual_roughness: 1.0,
+                    unlit: true,
+                    ..default()
+                }),
+            };
+            (archetype, assets)
+        })
+        .collect();
+
+    let bullet_mesh = meshes.add(Plane3d::new(Vec3::Y, Vec2::new(0.1, 1.0)));
     let player_bullet_material = bullet_materials.add(BulletMaterial {
         color: LinearRgba::new(0.2, 0.8, 0.2, 1.0),
     });
+    let enemy_bullet_material = bullet_materials.add(BulletMaterial {
+        color: LinearRgba::new(1.0, 0.1, 0.1, 1.0),
+    });
+
+    let beam_mesh = meshes.add(Plane3d::new(Vec3::Y, Vec2::new(0.3, 0.5)));
 
     let hex_plane_mesh = meshes.add(build_hex_plane(&arena.layout));
     let hex_plane_material = materials.add(StandardMaterial {
@@ -103,124 +205,45 @@ fn load_assets(
         ..default()
     });
 
-    // Tower images
     let tower_empty_image: Handle<Image> = asset_server.load("textures/empty.png");
-    let tower_bullet2_image: Handle<Image> = asset_server.load("textures/bullet2.png");
-    let tower_bullet3_image: Handle<Image> = asset_server.load("textures/bullet3.png");
-    let tower_bullet4_image: Handle<Image> = asset_server.load("textures/bullet4.png");
-    let tower_bullet6_image: Handle<Image> = asset_server.load("textures/bullet6.png");
-    let tower_explosion1_image: Handle<Image> = asset_server.load("textures/explosion1.png");
-    let tower_explosion2_image: Handle<Image> = asset_server.load("textures/explosion2.png");
-    let tower_explosion3_image: Handle<Image> = asset_server.load("textures/explosion3.png");
 
     let tower_mesh = meshes.add(build_tower_mesh(&arena.layout));
-    let base_tower_material = StandardMaterial {
-        base_color: Color::srgb(0.0, 0.1, 0.0),
-        perceptual_roughness: 1.0,
-        ..default()
-    };
-
-    let tower_materials = TowerAssets {
-        bullet2: tower_materials.add(ExtendedMaterial {
-            base: base_tower_material.clone(),
-            extension: TowerMaterial {
-                texture: tower_bullet2_image.clone(),
-            },
-        }),
-        bullet3: tower_materials.add(ExtendedMaterial {
-            base: base_tower_material.clone(),
-            extension: TowerMaterial {
-                texture: tower_bullet3_image.clone(),
-            },
-        }),
-        bullet4: tower_materials.add(ExtendedMaterial {
-            base: base_tower_material.clone(),
-            extension: TowerMaterial {
-                texture: tower_bullet4_image.clone(),
-            },
-        }),
-        bullet6: tower_materials.add(ExtendedMaterial {
-            base: base_tower_material.clone(),
-            extension: TowerMaterial {
-                texture: tower_bullet6_image.clone(),
-            },
-        }),
-        explosion1: tower_materials.add(ExtendedMaterial {
-            base: base_tower_material.clone(),
-            extension: TowerMaterial {
-                texture: tower_explosion1_image.clone(),
-            },
-        }),
-        explosion2: tower_materials.add(ExtendedMaterial {
-            base: base_tower_material.clone(),
-            extension: TowerMaterial {
-                texture: tower_explosion2_image.clone(),
-            },
-        }),
-        explosion3: tower_materials.add(ExtendedMaterial {
-            base: base_tower_material,
-            extension: TowerMaterial {
-                texture: tower_explosion3_image.clone(),
-            },
-        }),
-    };
+    let tower_materials = (1..=MAX_TOWER_LEVEL)
+        .map(|level| {
+            TowerAssets::new(tower_materials.add(ExtendedMaterial {
+                base: base_tower_material(),
+                extension: TowerMaterial {
+                    texture: tower_empty_image.clone(),
+                    highlight_color: tower_level_color(level),
+                    fuse: 0.0,
+                    charge: 1.0,
+                    health: 1.0,
+                },
+            }))
+        })
+        .collect();
 
     let tower_placeholder_mesh = meshes.add(build_tower_placeholder_mesh(&arena.layout));
     let tower_placeholder_empty_material =
         tower_placeholder_materials.add(TowerPlaceholderMaterial {
             texture: tower_empty_image,
         });
+    let tower_placeholder_materials = TowerAssets::new(tower_placeholder_empty_material.clone());
 
-    let tower_placeholder_materials = TowerAssets {
-        bullet2: tower_placeholder_materials.add(TowerPlaceholderMaterial {
-            texture: tower_bullet2_image,
-        }),
-        bullet3: tower_placeholder_materials.add(TowerPlaceholderMaterial {
-            texture: tower_bullet3_image,
-        }),
-        bullet4: tower_placeholder_materials.add(TowerPlaceholderMaterial {
-            texture: tower_bullet4_image,
-        }),
-        bullet6: tower_placeholder_materials.add(TowerPlaceholderMaterial {
-            texture: tower_bullet6_image,
-        }),
-        explosion1: tower_placeholder_materials.add(TowerPlaceholderMaterial {
-            texture: tower_explosion1_image,
-        }),
-        explosion2: tower_placeholder_materials.add(TowerPlaceholderMaterial {
-            texture: tower_explosion2_image,
-        }),
-        explosion3: tower_placeholder_materials.add(TowerPlaceholderMaterial {
-            texture: tower_explosion3_image,
-        }),
-    };
-
-    // Tower icons
     let tower_empty_icon: Handle<Image> = asset_server.load("icons/empty.png");
-    let tower_bullet2_icon: Handle<Image> = asset_server.load("icons/bullet2.png");
-    let tower_bullet3_icon: Handle<Image> = asset_server.load("icons/bullet3.png");
-    let tower_bullet4_icon: Handle<Image> = asset_server.load("icons/bullet4.png");
-    let tower_bullet6_icon: Handle<Image> = asset_server.load("icons/bullet6.png");
-    let tower_explosion1_icon: Handle<Image> = asset_server.load("icons/explosion1.png");
-    let tower_explosion2_icon: Handle<Image> = asset_server.load("icons/explosion2.png");
-    let tower_explosion3_icon: Handle<Image> = asset_server.load("icons/explosion3.png");
-
-    let tower_icons = TowerAssets {
-        bullet2: tower_bullet2_icon,
-        bullet3: tower_bullet3_icon,
-        bullet4: tower_bullet4_icon,
-        bullet6: tower_bullet6_icon,
-        explosion1: tower_explosion1_icon,
-        explosion2: tower_explosion2_icon,
-        explosion3: tower_explosion3_icon,
-    };
+    let tower_icons = TowerAssets::new(tower_empty_icon.clone());
 
     commands.insert_resource(GameAssets {
         audiowide_font,
-        enemy_mesh,
-        enemy_material,
-        player_bullet_mesh,
+        arena_column_mesh,
+        arena_column_material,
+        player_mesh,
+        player_material,
+        enemies,
+        bullet_mesh,
         player_bullet_material,
+        enemy_bullet_material,
+        beam_mesh,
         hex_plane_mesh,
         hex_plane_material,
         tower_mesh,
@@ -233,6 +256,68 @@ fn load_assets(
     });
 }
 
+fn base_tower_material() -> StandardMaterial {
+    StandardMaterial {
+        base_color: Color::srgb(0.0, 0.1, 0.0),
+        perceptual_roughness: 1.0,
+        ..default()
+    }
+}
+
+/// Creates the materials and icons for each tower once the tower definitions are (re)loaded.
+fn update_tower_assets(
+    mut evr_tower_list: EventReader<AssetEvent<TowerList>>,
+    asset_server: Res<AssetServer>,
+    tower_registry: Res<TowerRegistry>,
+    tower_lists: Res<Assets<TowerList>>,
+    mut game_assets: ResMut<GameAssets>,
+    mut tower_materials: ResMut<Assets<ExtendedMaterial<StandardMaterial, TowerMaterial>>>,
+    mut tower_placeholder_materials: ResMut<Assets<TowerPlaceholderMaterial>>,
+) {
+    let updated = evr_tower_list.read().any(|event| {
+        event.is_loaded_with_dependencies(&tower_registry.list)
+            || event.is_modified(&tower_registry.list)
+    });
+    if !updated {
+        return;
+    }
+    let Some(tower_list) = tower_lists.get(&tower_registry.list) else {
+        return;
+    };
+
+    for tower in tower_list.towers.iter() {
+        let texture: Handle<Image> = asset_server.load(&tower.texture);
+
+        for level in 1..=MAX_TOWER_LEVEL {
+            let material = tower_materials.add(ExtendedMaterial {
+                base: base_tower_material(),
+                extension: TowerMaterial {
+                    texture: texture.clone(),
+                    highlight_color: tower_level_color(level),
+                    fuse: 0.0,
+                    charge: 1.0,
+                    health: 1.0,
+                },
+            });
+            game_assets.tower_materials[(level - 1) as usize]
+                .handles
+                .insert(tower.id.clone(), material);
+        }
+
+        let placeholder_material =
+            tower_placeholder_materials.add(TowerPlaceholderMaterial { texture });
+        game_assets
+            .tower_placeholder_materials
+            .handles
+            .insert(tower.id.clone(), placeholder_material);
+
+        game_assets
+            .tower_icons
+            .handles
+            .insert(tower.id.clone(), asset_server.load(&tower.icon));
+    }
+}
+
 fn build_hex_plane(layout: &HexLayout) -> Mesh {
     let mesh_info = PlaneMeshBuilder::new(layout)
         .with_scale(Vec3::splat(0.9))
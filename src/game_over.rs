@@ -1,6 +1,15 @@
-use bevy::prelude::*;
-
-use crate::{AppState, game_assets::GameAssets, score::PlayerScore};
+use bevy::{input::keyboard::KeyboardInput, prelude::*};
+
+use crate::{
+    AppState,
+    difficulty::Difficulty,
+    game_assets::GameAssets,
+    high_scores::{HighScoreSet, NewHighScore},
+    menu::{seed_field, seed_input},
+    rng::{NextRunSeed, RunRng},
+    score::PlayerScore,
+    waves::{GameMode, WaveManager},
+};
 
 const NORMAL_BUTTON: Color = Color::srgb(1.0, 1.0, 1.0);
 const HOVERED_BUTTON: Color = Color::srgb(0.0, 0.63, 1.0);
@@ -10,12 +19,19 @@ pub struct GameOverPlugin;
 
 impl Plugin for GameOverPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(AppState::GameOver), setup_game_over)
-            .add_systems(OnExit(AppState::GameOver), cleanup_game_over)
-            .add_systems(
-                Update,
-                button_interaction.run_if(in_state(AppState::GameOver)),
-            );
+        app.add_systems(
+            OnEnter(AppState::GameOver),
+            setup_game_over.after(HighScoreSet),
+        )
+        .add_systems(OnExit(AppState::GameOver), cleanup_game_over)
+        .add_systems(
+            Update,
+            (
+                button_interaction,
+                seed_input.run_if(on_event::<KeyboardInput>),
+            )
+                .run_if(in_state(AppState::GameOver)),
+        );
     }
 }
 
@@ -26,6 +42,7 @@ pub struct GameOverUi;
 #[require(Button)]
 pub enum GameOverButton {
     Play,
+    Replay,
     Menu,
 }
 
@@ -35,6 +52,8 @@ pub struct GameOverCamera;
 
 fn button_interaction(
     mut next_app_state: ResMut<NextState<AppState>>,
+    mut next_seed: ResMut<NextRunSeed>,
+    run_rng: Res<RunRng>,
     mut q_interaction: Query<
         (
             &Interaction,
@@ -62,6 +81,10 @@ fn button_interaction(
                     GameOverButton::Play => {
                         next_app_state.set(AppState::InGame);
                     }
+                    GameOverButton::Replay => {
+                        next_seed.0 = Some(run_rng.seed());
+                        next_app_state.set(AppState::InGame);
+                    }
                     GameOverButton::Menu => {
                         next_app_state.set(AppState::Menu);
                     }
@@ -82,9 +105,25 @@ fn button_interaction(
     Ok(())
 }
 
-fn setup_game_over(mut commands: Commands, game_assets: Res<GameAssets>, score: Res<PlayerScore>) {
+fn setup_game_over(
+    mut commands: Commands,
+    game_assets: Res<GameAssets>,
+    score: Res<PlayerScore>,
+    run_rng: Res<RunRng>,
+    next_seed: Res<NextRunSeed>,
+    wave_manager: Res<WaveManager>,
+    game_mode: Res<GameMode>,
+    difficulty: Res<Difficulty>,
+    new_high_score: Option<Res<NewHighScore>>,
+) {
     commands.spawn(GameOverCamera);
 
+    let high_score_text = match new_high_score.and_then(|new_high_score| new_high_score.0) {
+        Some(0) => "New high score!".to_string(),
+        Some(rank) => format!("New high score! #{}", rank + 1),
+        None => String::new(),
+    };
+
     commands.spawn((
         GameOverUi,
         Node {
@@ -106,6 +145,15 @@ fn setup_game_over(mut commands: Commands, game_assets: Res<GameAssets>, score:
                     ..default()
                 },
             ),
+            (
+                Text::new(high_score_text),
+                TextColor(HOVERED_BUTTON),
+                TextFont {
+                    font: game_assets.audiowide_font.clone(),
+                    font_size: 30.0,
+                    ..default()
+                },
+            ),
             (
                 Node {
                     width: Val::Px(300.0),
@@ -116,6 +164,10 @@ fn setup_game_over(mut commands: Commands, game_assets: Res<GameAssets>, score:
                 },
                 children![
                     build_stat(format!("Score: {}", score.score), &game_assets),
+                    build_stat(
+                        format!("{} wave: {}", game_mode.name(), wave_manager.wave_display()),
+                        &game_assets
+                    ),
                     build_stat(
                         format!("Highest combo: {}", score.highest_combo),
                         &game_assets
@@ -124,9 +176,13 @@ fn setup_game_over(mut commands: Commands, game_assets: Res<GameAssets>, score:
                         format!("Highest chain kill: {}", score.highest_chain),
                         &game_assets
                     ),
+                    build_stat(format!("Difficulty: {}", difficulty.name()), &game_assets),
+                    build_stat(format!("Seed: {}", run_rng.seed()), &game_assets),
                 ]
             ),
+            seed_field(&next_seed, &game_assets),
             create_button(GameOverButton::Play, "Play again", &game_assets),
+            create_button(GameOverButton::Replay, "Replay seed", &game_assets),
             create_button(GameOverButton::Menu, "Main menu", &game_assets),
         ],
     ));
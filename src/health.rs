@@ -10,7 +10,7 @@ impl Plugin for HealthPlugin {
             .add_event::<HealEvent>()
             .add_event::<DiedEvent>()
             .add_systems(
-                Update,
+                FixedUpdate,
                 (
                     apply_heal_event.run_if(on_event::<HealEvent>),
                     apply_damage_event
@@ -0,0 +1,236 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    AppState,
+    difficulty::Difficulty,
+    persistence::{load_ron, save_ron},
+    replay::{finish_replay, is_replaying},
+    rng::RunRng,
+    score::PlayerScore,
+    waves::{GameMode, WaveManager},
+};
+
+const HIGH_SCORES_PATH: &str = "high_scores.ron";
+/// The number of entries kept for each mode and difficulty.
+const MAX_ENTRIES: usize = 10;
+
+/// Keeps the best runs for each mode and difficulty, saved to disk whenever a run makes the table.
+pub struct HighScorePlugin;
+
+impl Plugin for HighScorePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(load_high_scores())
+            .init_resource::<NewHighScore>()
+            .add_systems(OnEnter(AppState::InGame), reset_new_high_score)
+            .add_systems(
+                OnEnter(AppState::GameOver),
+                record_high_score
+                    .in_set(HighScoreSet)
+                    .run_if(not(is_replaying))
+                    .before(finish_replay),
+            );
+    }
+}
+
+/// Records the finished run in `HighScores`, anything showing the result on game over should run
+/// after this set.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct HighScoreSet;
+
+#[derive(Resource, Serialize, Deserialize, Default, Debug)]
+pub struct HighScores {
+    tables: Vec<HighScoreTable>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct HighScoreTable {
+    mode: GameMode,
+    difficulty: Difficulty,
+    /// Sorted from the highest score.
+    entries: Vec<HighScoreEntry>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct HighScoreEntry {
+    pub score: u128,
+    /// Seconds since the unix epoch when the run ended.
+    pub timestamp: u64,
+    /// The wave the run ended on (1, 2, 3, ...).
+    pub wave: usize,
+    pub seed: u64,
+    pub highest_combo: u128,
+    pub highest_chain: usize,
+}
+
+impl HighScoreEntry {
+    /// The date the run ended as `YYYY-MM-DD` (UTC).
+    pub fn date(&self) -> String {
+        // Converts days since the epoch to a civil date, see
+        // https://howardhinnant.github.io/date_algorithms.html#civil_from_days
+        let days = (self.timestamp / 86_400) as i64 + 719_468;
+        let era = days.div_euclid(146_097);
+        let day_of_era = days.rem_euclid(146_097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let month_index = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+        let month = if month_index < 10 {
+            month_index + 3
+        } else {
+            month_index - 9
+        };
+        let year = year_of_era + era * 400 + (month <= 2) as i64;
+        format!("{year:04}-{month:02}-{day:02}")
+    }
+}
+
+impl HighScores {
+    /// The best runs for a mode and difficulty, sorted from the highest score.
+    pub fn entries(&self, mode: GameMode, difficulty: Difficulty) -> &[HighScoreEntry] {
+        self.tables
+            .iter()
+            .find(|table| table.mode == mode && table.difficulty == difficulty)
+            .map(|table| table.entries.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Adds a run to the table, returning its rank (starting from 0) if it was good enough to be
+    /// kept.
+    pub fn insert(
+        &mut self,
+        mode: GameMode,
+        difficulty: Difficulty,
+        entry: HighScoreEntry,
+    ) -> Option<usize> {
+        let index = match self
+            .tables
+            .iter()
+            .position(|table| table.mode == mode && table.difficulty == difficulty)
+        {
+            Some(index) => index,
+            None => {
+                self.tables.push(HighScoreTable {
+                    mode,
+                    difficulty,
+                    entries: Vec::new(),
+                });
+                self.tables.len() - 1
+            }
+        };
+        let entries = &mut self.tables[index].entries;
+
+        // Ties go to the earlier run
+        let rank = entries
+            .iter()
+            .position(|existing| entry.score > existing.score)
+            .unwrap_or(entries.len());
+        if rank >= MAX_ENTRIES {
+            return None;
+        }
+        entries.insert(rank, entry);
+        entries.truncate(MAX_ENTRIES);
+        Some(rank)
+    }
+}
+
+/// The rank of the latest run in its high score table, `None` if it didn't make the table.
+#[derive(Resource, Default)]
+pub struct NewHighScore(pub Option<usize>);
+
+fn reset_new_high_score(mut new_high_score: ResMut<NewHighScore>) {
+    new_high_score.0 = None;
+}
+
+fn record_high_score(
+    mut high_scores: ResMut<HighScores>,
+    mut new_high_score: ResMut<NewHighScore>,
+    score: Res<PlayerScore>,
+    run_rng: Res<RunRng>,
+    wave_manager: Res<WaveManager>,
+    game_mode: Res<GameMode>,
+    difficulty: Res<Difficulty>,
+) {
+    let entry = HighScoreEntry {
+        score: score.score,
+        timestamp: unix_timestamp(),
+        wave: wave_manager.wave_display(),
+        seed: run_rng.seed(),
+        highest_combo: score.highest_combo,
+        highest_chain: score.highest_chain,
+    };
+    new_high_score.0 = high_scores.insert(*game_mode, *difficulty, entry);
+
+    if new_high_score.0.is_some() {
+        save_high_scores(&high_scores);
+    }
+}
+
+fn unix_timestamp() -> u64 {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+fn load_high_scores() -> HighScores {
+    load_ron(HIGH_SCORES_PATH, "high scores")
+}
+
+fn save_high_scores(high_scores: &HighScores) {
+    save_ron(HIGH_SCORES_PATH, "high scores", high_scores);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(score: u128) -> HighScoreEntry {
+        HighScoreEntry {
+            score,
+            timestamp: 0,
+            wave: 1,
+            seed: 0,
+            highest_combo: 0,
+            highest_chain: 0,
+        }
+    }
+
+    #[test]
+    fn high_scores_are_ranked_per_table() {
+        let mut high_scores = HighScores::default();
+        let (mode, difficulty) = (GameMode::Standard, Difficulty::Normal);
+
+        assert_eq!(high_scores.insert(mode, difficulty, entry(10)), Some(0));
+        assert_eq!(high_scores.insert(mode, difficulty, entry(30)), Some(0));
+        assert_eq!(high_scores.insert(mode, difficulty, entry(20)), Some(1));
+        assert_eq!(
+            high_scores.insert(GameMode::Endless, difficulty, entry(5)),
+            Some(0)
+        );
+
+        let scores: Vec<u128> = high_scores
+            .entries(mode, difficulty)
+            .iter()
+            .map(|entry| entry.score)
+            .collect();
+        assert_eq!(scores, vec![30, 20, 10]);
+
+        for _ in 0..MAX_ENTRIES {
+            high_scores.insert(mode, difficulty, entry(100));
+        }
+        assert_eq!(high_scores.insert(mode, difficulty, entry(1)), None);
+        assert_eq!(high_scores.entries(mode, difficulty).len(), MAX_ENTRIES);
+    }
+
+    #[test]
+    fn high_score_date() {
+        let mut entry = entry(0);
+        assert_eq!(entry.date(), "1970-01-01");
+        entry.timestamp = 1_709_210_096;
+        assert_eq!(entry.date(), "2024-02-29");
+    }
+}
@@ -2,9 +2,9 @@ use bevy::prelude::*;
 
 use crate::{
     AppState, GameState,
-    building::{BuildingSettings, BuildingsUpdatedEvent},
-    game_assets::GameAssets,
-    tower::TowerKind,
+    building::{BuildingSettings, BuildingsUpdatedEvent, reset_building_settings},
+    game_assets::{GameAssets, tower_icon_color},
+    replay::is_replaying,
 };
 
 const GREEN: Color = Color::srgb(0.15, 0.62, 0.33);
@@ -15,18 +15,21 @@ pub struct HotbarPlugin;
 
 impl Plugin for HotbarPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(AppState::InGame), setup_hotbar)
-            .add_systems(OnExit(AppState::InGame), cleanup_hotbar)
-            .add_systems(OnExit(GameState::RewardSelect), redraw_hotbar_images)
-            .add_systems(
-                Update,
-                (
-                    hotbar_interactions,
-                    redraw_hotbar_images.run_if(on_event::<BuildingsUpdatedEvent>),
-                )
-                    .run_if(in_state(AppState::InGame))
-                    .run_if(in_state(GameState::Running).or(in_state(GameState::Building))),
-            );
+        app.add_systems(
+            OnEnter(AppState::InGame),
+            setup_hotbar.after(reset_building_settings),
+        )
+        .add_systems(OnExit(AppState::InGame), cleanup_hotbar)
+        .add_systems(OnExit(GameState::RewardSelect), redraw_hotbar_images)
+        .add_systems(
+            Update,
+            (
+                hotbar_interactions.run_if(not(is_replaying)),
+                redraw_hotbar_images.run_if(on_event::<BuildingsUpdatedEvent>),
+            )
+                .run_if(in_state(AppState::InGame))
+                .run_if(in_state(GameState::Running).or(in_state(GameState::Building))),
+        );
     }
 }
 
@@ -111,26 +114,34 @@ fn redraw_hotbar_images(
 ) -> Result {
     for (hotbar_button, children) in q_hotbar_button {
         let mut image_node = q_image_node.get_mut(*children.first().unwrap())?;
-        image_node.image = building_settings
-            .towers
-            .get(hotbar_button.index)
-            .map(|t| game_assets.tower_icons.get(t))
-            .unwrap_or_else(|| game_assets.tower_empty_icon.clone());
+        *image_node = hotbar_image(hotbar_button.index, &game_assets, &building_settings);
     }
 
     Ok(())
 }
 
+/// The icon of the tower in a hotbar slot, tinted with the tower's level.
+fn hotbar_image(
+    index: usize,
+    game_assets: &GameAssets,
+    building_settings: &BuildingSettings,
+) -> ImageNode {
+    match building_settings.towers.get(index) {
+        Some(tower) => ImageNode {
+            image: game_assets.tower_icons.get(&tower.id),
+            color: tower_icon_color(tower.level),
+            ..default()
+        },
+        None => ImageNode::new(game_assets.tower_empty_icon.clone()),
+    }
+}
+
 fn hotbar_button(
     index: usize,
     game_assets: &GameAssets,
     building_settings: &BuildingSettings,
 ) -> impl Bundle {
-    let image = building_settings
-        .towers
-        .get(index)
-        .map(|t| game_assets.tower_icons.get(t))
-        .unwrap_or_else(|| game_assets.tower_empty_icon.clone());
+    let image = hotbar_image(index, game_assets, building_settings);
 
     return (
         Node {
@@ -151,7 +162,7 @@ fn hotbar_button(
                 BackgroundColor(Color::srgb(0.1, 0.1, 0.1)),
                 BorderColor(GREY),
                 BorderRadius::all(Val::Px(6.0)),
-                children![ImageNode { image, ..default() }]
+                children![image]
             ),
             (
                 Text::new((index + 1).to_string()),
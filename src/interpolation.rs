@@ -0,0 +1,72 @@
+use bevy::{
+    app::{RunFixedMainLoop, RunFixedMainLoopSystem},
+    prelude::*,
+};
+
+pub struct InterpolationPlugin;
+
+impl Plugin for InterpolationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(init_interpolation)
+            .add_systems(
+                RunFixedMainLoop,
+                (
+                    restore_translation.in_set(RunFixedMainLoopSystem::BeforeFixedMainLoop),
+                    interpolate_translation.in_set(RunFixedMainLoopSystem::AfterFixedMainLoop),
+                ),
+            )
+            .add_systems(FixedFirst, store_previous_translation)
+            .add_systems(FixedLast, store_current_translation);
+    }
+}
+
+/// Smooths the rendered translation of entities that move in `FixedUpdate`.
+///
+/// The simulation only ever sees the fixed translation, the interpolated translation is written
+/// to `Transform` after the fixed loop and restored before the next one. Without
+/// `InterpolationPlugin` (e.g. headless) this does nothing.
+#[derive(Component, Default)]
+pub struct InterpolateTranslation {
+    /// The translation at the start of the latest fixed tick.
+    previous: Vec3,
+    /// The translation at the end of the latest fixed tick.
+    current: Vec3,
+}
+
+fn init_interpolation(
+    trigger: Trigger<OnAdd, InterpolateTranslation>,
+    mut q_interpolate: Query<(&mut InterpolateTranslation, &Transform)>,
+) -> Result {
+    let (mut interpolate, transform) = q_interpolate.get_mut(trigger.target())?;
+    interpolate.previous = transform.translation;
+    interpolate.current = transform.translation;
+    Ok(())
+}
+
+fn restore_translation(mut q_interpolate: Query<(&InterpolateTranslation, &mut Transform)>) {
+    for (interpolate, mut transform) in q_interpolate.iter_mut() {
+        transform.translation = interpolate.current;
+    }
+}
+
+fn store_previous_translation(mut q_interpolate: Query<(&mut InterpolateTranslation, &Transform)>) {
+    for (mut interpolate, transform) in q_interpolate.iter_mut() {
+        interpolate.previous = transform.translation;
+    }
+}
+
+fn store_current_translation(mut q_interpolate: Query<(&mut InterpolateTranslation, &Transform)>) {
+    for (mut interpolate, transform) in q_interpolate.iter_mut() {
+        interpolate.current = transform.translation;
+    }
+}
+
+fn interpolate_translation(
+    fixed_time: Res<Time<Fixed>>,
+    mut q_interpolate: Query<(&InterpolateTranslation, &mut Transform)>,
+) {
+    let alpha = fixed_time.overstep_fraction();
+    for (interpolate, mut transform) in q_interpolate.iter_mut() {
+        transform.translation = interpolate.previous.lerp(interpolate.current, alpha);
+    }
+}
@@ -0,0 +1,291 @@
+use bevy::prelude::*;
+
+use crate::{
+    AppState,
+    difficulty::Difficulty,
+    game_assets::GameAssets,
+    high_scores::{HighScoreEntry, HighScores},
+    waves::GameMode,
+};
+
+const NORMAL_BUTTON: Color = Color::srgb(1.0, 1.0, 1.0);
+const HOVERED_BUTTON: Color = Color::srgb(0.0, 0.63, 1.0);
+const PRESSED_BUTTON: Color = Color::srgb(0.11, 0.3, 0.41);
+const COLUMN_WIDTHS: [f32; 7] = [50.0, 160.0, 80.0, 90.0, 80.0, 140.0, 140.0];
+
+pub struct LeaderboardPlugin;
+
+impl Plugin for LeaderboardPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::Leaderboard), setup_leaderboard)
+            .add_systems(OnExit(AppState::Leaderboard), cleanup_leaderboard)
+            .add_systems(
+                Update,
+                (
+                    button_interaction,
+                    redraw_rows.run_if(resource_exists_and_changed::<LeaderboardFilter>),
+                )
+                    .chain()
+                    .run_if(in_state(AppState::Leaderboard)),
+            );
+    }
+}
+
+/// The high score table being shown.
+#[derive(Resource)]
+struct LeaderboardFilter {
+    mode: GameMode,
+    difficulty: Difficulty,
+}
+
+#[derive(Component)]
+struct LeaderboardUi;
+
+#[derive(Component)]
+#[require(Camera2d)]
+struct LeaderboardCamera;
+
+/// The container the table rows are spawned in.
+#[derive(Component)]
+struct LeaderboardRows;
+
+#[derive(Component)]
+#[require(Button)]
+enum LeaderboardButton {
+    Mode,
+    Difficulty,
+    Back,
+}
+
+fn setup_leaderboard(
+    mut commands: Commands,
+    game_assets: Res<GameAssets>,
+    game_mode: Res<GameMode>,
+    difficulty: Res<Difficulty>,
+) {
+    // Start on the table for the current settings
+    commands.insert_resource(LeaderboardFilter {
+        mode: *game_mode,
+        difficulty: *difficulty,
+    });
+
+    commands.spawn(LeaderboardCamera);
+
+    commands.spawn((
+        LeaderboardUi,
+        Node {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(30.0),
+            ..default()
+        },
+        children![
+            (
+                Text::new("Leaderboard"),
+                TextFont {
+                    font: game_assets.audiowide_font.clone(),
+                    font_size: 60.0,
+                    ..default()
+                },
+            ),
+            (
+                Node {
+                    column_gap: Val::Px(20.0),
+                    ..default()
+                },
+                children![
+                    create_button(
+                        LeaderboardButton::Mode,
+                        &mode_display(*game_mode),
+                        &game_assets
+                    ),
+                    create_button(
+                        LeaderboardButton::Difficulty,
+                        &difficulty_display(*difficulty),
+                        &game_assets
+                    ),
+                ],
+            ),
+            (
+                LeaderboardRows,
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(5.0),
+                    ..default()
+                },
+            ),
+            create_button(LeaderboardButton::Back, "Main menu", &game_assets),
+        ],
+    ));
+}
+
+fn cleanup_leaderboard(
+    mut commands: Commands,
+    ui_id: Single<Entity, With<LeaderboardUi>>,
+    camera_id: Single<Entity, With<LeaderboardCamera>>,
+) {
+    commands.entity(*ui_id).despawn();
+    commands.entity(*camera_id).despawn();
+    commands.remove_resource::<LeaderboardFilter>();
+}
+
+fn button_interaction(
+    mut next_app_state: ResMut<NextState<AppState>>,
+    mut filter: ResMut<LeaderboardFilter>,
+    mut q_interaction: Query<
+        (
+            &Interaction,
+            &LeaderboardButton,
+            &mut Button,
+            &mut BorderColor,
+            &Children,
+        ),
+        Changed<Interaction>,
+    >,
+    mut q_text_color: Query<&mut TextColor>,
+    mut q_text: Query<&mut Text>,
+) -> Result {
+    for (interaction, leaderboard_button, mut button, mut border_color, children) in
+        q_interaction.iter_mut()
+    {
+        let mut text_color = q_text_color.get_mut(children[0])?;
+
+        match *interaction {
+            Interaction::Pressed => {
+                *text_color = TextColor(PRESSED_BUTTON);
+                *border_color = BorderColor(PRESSED_BUTTON);
+                button.set_changed();
+
+                match *leaderboard_button {
+                    LeaderboardButton::Mode => {
+                        filter.mode = match filter.mode {
+                            GameMode::Standard => GameMode::Endless,
+                            GameMode::Endless => GameMode::Standard,
+                        };
+                        q_text.get_mut(children[0])?.0 = mode_display(filter.mode);
+                    }
+                    LeaderboardButton::Difficulty => {
+                        filter.difficulty = filter.difficulty.next();
+                        q_text.get_mut(children[0])?.0 = difficulty_display(filter.difficulty);
+                    }
+                    LeaderboardButton::Back => {
+                        next_app_state.set(AppState::Menu);
+                    }
+                }
+            }
+            Interaction::Hovered => {
+                *text_color = TextColor(HOVERED_BUTTON);
+                *border_color = BorderColor(HOVERED_BUTTON);
+                button.set_changed();
+            }
+            Interaction::None => {
+                *text_color = TextColor(NORMAL_BUTTON);
+                *border_color = BorderColor(NORMAL_BUTTON);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn redraw_rows(
+    mut commands: Commands,
+    game_assets: Res<GameAssets>,
+    high_scores: Res<HighScores>,
+    filter: Res<LeaderboardFilter>,
+    rows_id: Single<Entity, With<LeaderboardRows>>,
+) {
+    let entries = high_scores.entries(filter.mode, filter.difficulty);
+
+    commands
+        .entity(*rows_id)
+        .despawn_related::<Children>()
+        .with_children(|parent| {
+            if entries.is_empty() {
+                parent.spawn(table_text("No runs yet".to_string(), &game_assets));
+                return;
+            }
+
+            let header = ["#", "Score", "Wave", "Combo", "Chain", "Seed", "Date"];
+            spawn_row(parent, header.map(String::from), &game_assets);
+            for (rank, entry) in entries.iter().enumerate() {
+                spawn_row(parent, entry_columns(rank, entry), &game_assets);
+            }
+        });
+}
+
+fn entry_columns(rank: usize, entry: &HighScoreEntry) -> [String; 7] {
+    [
+        (rank + 1).to_string(),
+        entry.score.to_string(),
+        entry.wave.to_string(),
+        entry.highest_combo.to_string(),
+        entry.highest_chain.to_string(),
+        entry.seed.to_string(),
+        entry.date(),
+    ]
+}
+
+fn spawn_row(parent: &mut ChildSpawnerCommands, columns: [String; 7], game_assets: &GameAssets) {
+    parent.spawn(Node::default()).with_children(|row| {
+        for (text, width) in columns.into_iter().zip(COLUMN_WIDTHS) {
+            row.spawn((
+                Node {
+                    width: Val::Px(width),
+                    ..default()
+                },
+                table_text(text, game_assets),
+            ));
+        }
+    });
+}
+
+fn table_text(text: String, game_assets: &GameAssets) -> impl Bundle {
+    (
+        Text::new(text),
+        TextFont {
+            font: game_assets.audiowide_font.clone(),
+            font_size: 20.0,
+            ..default()
+        },
+    )
+}
+
+fn mode_display(mode: GameMode) -> String {
+    format!("Mode: {}", mode.name())
+}
+
+fn difficulty_display(difficulty: Difficulty) -> String {
+    format!("Difficulty: {}", difficulty.name())
+}
+
+fn create_button(
+    button: LeaderboardButton,
+    text: &str,
+    game_assets: &GameAssets,
+) -> impl Bundle + use<> {
+    (
+        button,
+        Node {
+            width: Val::Px(300.0),
+            border: UiRect::all(Val::Px(2.0)),
+            padding: UiRect::all(Val::Px(8.0)),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        BorderColor(NORMAL_BUTTON),
+        BorderRadius::all(Val::Px(6.0)),
+        children![
+            Text::new(text),
+            TextColor(NORMAL_BUTTON),
+            TextFont {
+                font: game_assets.audiowide_font.clone(),
+                font_size: 30.0,
+                ..default()
+            }
+        ],
+    )
+}
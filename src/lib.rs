@@ -0,0 +1,224 @@
+use bevy::{
+    app::PluginGroupBuilder,
+    asset::AssetMetaCheck,
+    input::InputPlugin,
+    prelude::*,
+    state::{app::StatesPlugin, state::StateTransition},
+    window::ExitCondition,
+};
+
+use crate::{
+    arena_index::ArenaIndexSet,
+    bullet::BulletSet,
+    enemy::EnemySet,
+    explosion::ExplosionSet,
+    flow_field::FlowFieldSet,
+    force::ForceSet,
+    health::HealthSet,
+    player::{PlayerInputSet, PlayerSet},
+    score::ScoreSet,
+    tower::TowerSet,
+};
+
+pub mod arena;
+pub mod arena_index;
+pub mod building;
+pub mod bullet;
+pub mod controls;
+pub mod difficulty;
+pub mod enemy;
+pub mod explosion;
+pub mod flow_field;
+pub mod force;
+pub mod game_assets;
+pub mod game_over;
+pub mod health;
+pub mod high_scores;
+pub mod hotbar;
+pub mod interpolation;
+pub mod leaderboard;
+pub mod loading;
+pub mod materials;
+pub mod menu;
+pub mod pause;
+pub mod persistence;
+pub mod player;
+pub mod pointer_tracking;
+pub mod replay;
+pub mod reward_select;
+pub mod rng;
+pub mod ron_list;
+pub mod score;
+pub mod score_ui;
+pub mod settings;
+pub mod sound;
+pub mod tower;
+pub mod waves;
+
+#[derive(States, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum AppState {
+    Loading,
+    Menu,
+    InGame,
+    GameOver,
+    Leaderboard,
+    Settings,
+}
+
+#[derive(States, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum GameState {
+    Running,
+    Paused,
+    Building,
+    RewardSelect,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Team {
+    Player,
+    Enemy,
+}
+
+#[derive(Component, Default)]
+pub struct PlayerTeam;
+
+#[derive(Component, Default)]
+pub struct EnemyTeam;
+
+/// The full game, including windowing, rendering, assets and UI.
+pub struct GamePlugins;
+
+impl PluginGroup for GamePlugins {
+    fn build(self) -> PluginGroupBuilder {
+        let group = PluginGroupBuilder::start::<Self>()
+            // Defaults
+            .add_group(DefaultPlugins.set(AssetPlugin {
+                // Wasm builds will check for meta files (that don't exist) if this isn't set.
+                // This causes errors and even panics in web builds on itch.
+                // See https://github.com/bevyengine/bevy_github_ci_template/issues/48.
+                meta_check: AssetMetaCheck::Never,
+                ..Default::default()
+            }))
+            .add(MeshPickingPlugin)
+            .add(DisplayPlugin);
+
+        add_simulation_plugins(group)
+            .add(replay::ReplayPlugin { save_replays: true })
+            .add(controls::ControlsPlugin { persist: true })
+            .add(high_scores::HighScorePlugin)
+            // Presentation
+            .add(menu::MenuPlugin)
+            .add(pause::PausePlugin)
+            .add(game_assets::GameAssetPlugin)
+            .add(materials::MaterialsPlugin)
+            .add(interpolation::InterpolationPlugin)
+            .add(pointer_tracking::PointerTrackingPlugin)
+            .add(building::BuildingPlugin)
+            .add(score_ui::ScoreUiPlugin)
+            .add(reward_select::RewardSelectPlugin)
+            .add(hotbar::HotbarPlugin)
+            .add(game_over::GameOverPlugin)
+            .add(leaderboard::LeaderboardPlugin)
+            .add(settings::SettingsPlugin)
+            .add(sound::SoundPlugin)
+    }
+}
+
+/// Only the game simulation (player, enemies, waves, towers, explosions and score), running on
+/// `MinimalPlugins` without windows, rendering, `GameAssets` or UI.
+///
+/// Useful for integration tests and tools that need to tick the combat loop without a GPU.
+pub struct HeadlessGamePlugins;
+
+impl PluginGroup for HeadlessGamePlugins {
+    fn build(self) -> PluginGroupBuilder {
+        let group = PluginGroupBuilder::start::<Self>()
+            // Defaults
+            .add_group(MinimalPlugins)
+            .add(StatesPlugin)
+            .add(InputPlugin)
+            .add(AssetPlugin::default())
+            // Registers window events (e.g. `CursorMoved`) without opening a window
+            .add(WindowPlugin {
+                primary_window: None,
+                exit_condition: ExitCondition::DontExit,
+                ..default()
+            });
+
+        add_simulation_plugins(group)
+            .add(replay::ReplayPlugin {
+                save_replays: false,
+            })
+            .add(controls::ControlsPlugin { persist: false })
+    }
+}
+
+/// Adds the states and all plugins needed to simulate a game.
+fn add_simulation_plugins(group: PluginGroupBuilder) -> PluginGroupBuilder {
+    group
+        .add(SimulationPlugin)
+        .add(loading::LoadingPlugin)
+        .add(rng::RngPlugin)
+        .add(difficulty::DifficultyPlugin)
+        .add(player::PlayerPlugin)
+        .add(enemy::EnemyPlugin)
+        .add(bullet::BulletPlugin)
+        .add(waves::WavePlugin)
+        .add(arena::ArenaPlugin)
+        .add(health::HealthPlugin)
+        .add(force::ForcePlugin)
+        .add(arena_index::ArenaIndexPlugin)
+        .add(flow_field::FlowFieldPlugin)
+        .add(building::BuildingSettingsPlugin)
+        .add(tower::TowerPlugin)
+        .add(explosion::ExplosionPlugin)
+        .add(score::ScorePlugin)
+}
+
+/// Inserts the game states and orders the simulation, which runs in `FixedUpdate` so outcomes
+/// don't depend on frame rate.
+struct SimulationPlugin;
+
+impl Plugin for SimulationPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_state(AppState::Loading)
+            .insert_state(GameState::Running)
+            .configure_sets(
+                FixedUpdate,
+                (
+                    PlayerInputSet,
+                    // Index positions from the previous tick before anything reads them
+                    ArenaIndexSet,
+                    PlayerSet,
+                    FlowFieldSet,
+                    EnemySet,
+                    BulletSet,
+                    TowerSet,
+                    ExplosionSet,
+                    ForceSet,
+                    HealthSet,
+                    ScoreSet,
+                )
+                    .chain(),
+            )
+            .add_systems(FixedPreUpdate, apply_state_transitions);
+    }
+}
+
+/// State changes are applied at the start of each fixed tick (as well as each frame), so they
+/// happen on the same tick regardless of frame rate and replays stay in sync.
+fn apply_state_transitions(world: &mut World) {
+    world.run_schedule(StateTransition);
+}
+
+struct DisplayPlugin;
+
+impl Plugin for DisplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(MeshPickingSettings {
+            require_markers: true,
+            ray_cast_visibility: RayCastVisibility::Any,
+        })
+        .insert_resource(ClearColor(Color::hsl(0.0, 0.0, 0.015)));
+    }
+}
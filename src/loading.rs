@@ -1,15 +1,38 @@
 use bevy::prelude::*;
 
-use crate::{AppState, game_assets::GameAssetSet};
+use crate::{AppState, replay::ReplayPlayback};
 
 pub struct LoadingPlugin;
 
 impl Plugin for LoadingPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, finish_loading.after(GameAssetSet));
+        app.init_resource::<LoadingAssets>()
+            .add_systems(Update, finish_loading.run_if(in_state(AppState::Loading)));
     }
 }
 
-fn finish_loading(mut next_app_state: ResMut<NextState<AppState>>) {
-    next_app_state.set(AppState::Menu);
+/// Assets that must finish loading before leaving `AppState::Loading`.
+#[derive(Resource, Default, Deref, DerefMut)]
+pub struct LoadingAssets(pub Vec<UntypedHandle>);
+
+fn finish_loading(
+    mut next_app_state: ResMut<NextState<AppState>>,
+    asset_server: Res<AssetServer>,
+    loading_assets: Res<LoadingAssets>,
+    playback: Option<Res<ReplayPlayback>>,
+) {
+    // Failed loads are logged by the asset server, keep waiting so they can be fixed with a reload
+    if !loading_assets
+        .iter()
+        .all(|handle| asset_server.is_loaded_with_dependencies(handle))
+    {
+        return;
+    }
+
+    if playback.is_some() {
+        // Replays skip the menu
+        next_app_state.set(AppState::InGame);
+    } else {
+        next_app_state.set(AppState::Menu);
+    }
 }
@@ -13,7 +13,8 @@ impl Plugin for MaterialsPlugin {
                 ExtendedMaterial<StandardMaterial, TowerMaterial>,
             >::default())
             .add_plugins(MaterialPlugin::<TowerPlaceholderMaterial>::default())
-            .add_plugins(MaterialPlugin::<ExplodingRingMaterial>::default());
+            .add_plugins(MaterialPlugin::<ExplodingRingMaterial>::default())
+            .add_plugins(MaterialPlugin::<BeamMaterial>::default());
     }
 }
 
@@ -38,6 +39,20 @@ pub struct TowerMaterial {
     #[texture(100, dimension = "2d")]
     #[sampler(101)]
     pub texture: Handle<Image>,
+    /// The color of the texture's lines and the edge of the top, shows the tower's level.
+    #[uniform(102)]
+    pub highlight_color: LinearRgba,
+    /// How much of an armed fuse is left, shown as a ring counting down. 0.0 when not armed.
+    #[uniform(102)]
+    pub fuse: f32,
+    /// How charged the tower is, see `TowerCharges::fraction`. Recharging towers are dimmed with a
+    /// ring filling up, 1.0 when full.
+    #[uniform(102)]
+    pub charge: f32,
+    /// The fraction of health the tower has left, damaged towers are tinted red. 1.0 when
+    /// undamaged.
+    #[uniform(102)]
+    pub health: f32,
 }
 
 impl MaterialExtension for TowerMaterial {
@@ -84,3 +99,24 @@ impl Material for ExplodingRingMaterial {
         AlphaMode::Blend
     }
 }
+
+#[derive(Asset, TypePath, AsBindGroup, Debug, Clone, Default)]
+pub struct BeamMaterial {
+    #[uniform(0)]
+    pub color: LinearRgba,
+    /// The time since the beam was fired, the beam fades out over its duration.
+    #[uniform(0)]
+    pub time: f32,
+    #[uniform(0)]
+    pub duration: f32,
+}
+
+impl Material for BeamMaterial {
+    fn fragment_shader() -> ShaderRef {
+        "shaders/beam.wgsl".into()
+    }
+
+    fn alpha_mode(&self) -> AlphaMode {
+        AlphaMode::Blend
+    }
+}
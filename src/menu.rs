@@ -1,6 +1,11 @@
-use bevy::prelude::*;
+use bevy::{
+    input::keyboard::{Key, KeyboardInput},
+    prelude::*,
+};
 
-use crate::{AppState, game_assets::GameAssets};
+use crate::{
+    AppState, difficulty::Difficulty, game_assets::GameAssets, rng::NextRunSeed, waves::GameMode,
+};
 
 const NORMAL_BUTTON: Color = Color::srgb(1.0, 1.0, 1.0);
 const HOVERED_BUTTON: Color = Color::srgb(0.0, 0.63, 1.0);
@@ -12,7 +17,14 @@ impl Plugin for MenuPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(OnEnter(AppState::Menu), setup_menu)
             .add_systems(OnExit(AppState::Menu), cleanup_menu)
-            .add_systems(Update, button_interaction.run_if(in_state(AppState::Menu)));
+            .add_systems(
+                Update,
+                (
+                    button_interaction,
+                    seed_input.run_if(on_event::<KeyboardInput>),
+                )
+                    .run_if(in_state(AppState::Menu)),
+            );
     }
 }
 
@@ -23,13 +35,28 @@ pub struct Menu;
 #[require(Button)]
 pub enum MenuButton {
     Play,
+    Endless,
+    /// Cycles through the difficulty presets.
+    Difficulty,
+    Leaderboard,
+    Settings,
     Exit,
 }
 
 #[derive(Component)]
 pub struct MenuCamera;
 
-fn setup_menu(mut commands: Commands, game_assets: Res<GameAssets>) {
+/// Displays the seed for the next run, typing digits changes `NextRunSeed`. Screens with a
+/// `seed_field` need to run `seed_input`.
+#[derive(Component)]
+pub struct SeedInput;
+
+fn setup_menu(
+    mut commands: Commands,
+    game_assets: Res<GameAssets>,
+    next_seed: Res<NextRunSeed>,
+    difficulty: Res<Difficulty>,
+) {
     commands.spawn((
         Menu,
         Node {
@@ -54,7 +81,16 @@ fn setup_menu(mut commands: Commands, game_assets: Res<GameAssets>) {
                     ..default()
                 },
             ),
+            seed_field(&next_seed, &game_assets),
             create_button(MenuButton::Play, "Play Game", &game_assets),
+            create_button(MenuButton::Endless, "Endless", &game_assets),
+            create_button(
+                MenuButton::Difficulty,
+                &difficulty_display(*difficulty),
+                &game_assets
+            ),
+            create_button(MenuButton::Leaderboard, "Leaderboard", &game_assets),
+            create_button(MenuButton::Settings, "Settings", &game_assets),
             create_button(MenuButton::Exit, "Exit", &game_assets),
         ],
     ));
@@ -89,6 +125,9 @@ fn button_interaction(
         Changed<Interaction>,
     >,
     mut q_text_color: Query<&mut TextColor>,
+    mut q_text: Query<&mut Text>,
+    mut game_mode: ResMut<GameMode>,
+    mut difficulty: ResMut<Difficulty>,
     mut evw_app_exit: EventWriter<AppExit>,
 ) -> Result {
     for (interaction, menu_button, mut button, mut border_color, children) in
@@ -104,8 +143,23 @@ fn button_interaction(
 
                 match *menu_button {
                     MenuButton::Play => {
+                        *game_mode = GameMode::Standard;
+                        commands.set_state(AppState::InGame);
+                    }
+                    MenuButton::Endless => {
+                        *game_mode = GameMode::Endless;
                         commands.set_state(AppState::InGame);
                     }
+                    MenuButton::Difficulty => {
+                        *difficulty = difficulty.next();
+                        q_text.get_mut(children[0])?.0 = difficulty_display(*difficulty);
+                    }
+                    MenuButton::Leaderboard => {
+                        commands.set_state(AppState::Leaderboard);
+                    }
+                    MenuButton::Settings => {
+                        commands.set_state(AppState::Settings);
+                    }
                     MenuButton::Exit => {
                         evw_app_exit.write(AppExit::Success);
                     }
@@ -126,6 +180,82 @@ fn button_interaction(
     Ok(())
 }
 
+/// A labelled `SeedInput`.
+pub fn seed_field(next_seed: &NextRunSeed, game_assets: &GameAssets) -> impl Bundle + use<> {
+    (
+        Node {
+            column_gap: Val::Px(10.0),
+            ..default()
+        },
+        children![
+            (
+                Text::new("Seed (type to change):"),
+                TextFont {
+                    font: game_assets.audiowide_font.clone(),
+                    font_size: 20.0,
+                    ..default()
+                },
+            ),
+            (
+                SeedInput,
+                Text::new(seed_display(next_seed)),
+                TextColor(HOVERED_BUTTON),
+                TextFont {
+                    font: game_assets.audiowide_font.clone(),
+                    font_size: 20.0,
+                    ..default()
+                },
+            ),
+        ],
+    )
+}
+
+pub fn seed_input(
+    mut evr_keyboard: EventReader<KeyboardInput>,
+    mut next_seed: ResMut<NextRunSeed>,
+    mut seed_text: Single<&mut Text, With<SeedInput>>,
+) {
+    for event in evr_keyboard.read() {
+        if !event.state.is_pressed() {
+            continue;
+        }
+
+        match &event.logical_key {
+            Key::Character(character) => {
+                let Ok(digit) = character.parse::<u64>() else {
+                    continue;
+                };
+                // Append the digit, ignoring it if the seed would overflow
+                if let Some(seed) = next_seed
+                    .0
+                    .unwrap_or(0)
+                    .checked_mul(10)
+                    .and_then(|seed| seed.checked_add(digit))
+                {
+                    next_seed.0 = Some(seed);
+                }
+            }
+            Key::Backspace => {
+                next_seed.0 = next_seed.0.map(|seed| seed / 10).filter(|seed| *seed > 0);
+            }
+            _ => {}
+        }
+    }
+
+    seed_text.0 = seed_display(&next_seed);
+}
+
+fn seed_display(next_seed: &NextRunSeed) -> String {
+    next_seed
+        .0
+        .map(|seed| seed.to_string())
+        .unwrap_or_else(|| "Random".to_string())
+}
+
+fn difficulty_display(difficulty: Difficulty) -> String {
+    format!("Difficulty: {}", difficulty.name())
+}
+
 fn create_button(button: MenuButton, text: &str, game_assets: &GameAssets) -> impl Bundle {
     (
         button,
@@ -1,6 +1,9 @@
-use bevy::{input::common_conditions::input_just_pressed, prelude::*};
+use bevy::prelude::*;
 
-use crate::{AppState, GameState};
+use crate::{
+    AppState, GameState,
+    controls::{InputAction, action_just_pressed},
+};
 
 const NORMAL_BUTTON: Color = Color::srgb(1.0, 1.0, 1.0);
 const HOVERED_BUTTON: Color = Color::srgb(0.0, 0.63, 1.0);
@@ -18,10 +21,10 @@ impl Plugin for PausePlugin {
                 (
                     pause_game
                         .run_if(in_state(GameState::Running))
-                        .run_if(input_just_pressed(KeyCode::Escape)),
+                        .run_if(action_just_pressed(InputAction::Pause)),
                     unpause_game
                         .run_if(in_state(GameState::Paused))
-                        .run_if(input_just_pressed(KeyCode::Escape)),
+                        .run_if(action_just_pressed(InputAction::Pause)),
                     button_interaction.run_if(in_state(GameState::Paused)),
                 )
                     .run_if(in_state(AppState::InGame)),
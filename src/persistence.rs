@@ -0,0 +1,45 @@
+use bevy::prelude::*;
+use serde::{Serialize, de::DeserializeOwned};
+
+/// Loads a value saved with `save_ron`, `name` is used in log messages. Missing or broken files
+/// load the default.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn load_ron<T: DeserializeOwned + Default>(path: &str, name: &str) -> T {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return T::default(),
+        Err(error) => {
+            error!(path, %error, "Failed to read {name}");
+            return T::default();
+        }
+    };
+
+    match ron::from_str(&contents) {
+        Ok(value) => value,
+        Err(error) => {
+            error!(path, %error, "Failed to parse {name}");
+            T::default()
+        }
+    }
+}
+
+/// Saves a value to `path` as RON, errors are logged.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn save_ron<T: Serialize>(path: &str, name: &str, value: &T) {
+    let result = ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default())
+        .map_err(|error| error.to_string())
+        .and_then(|contents| std::fs::write(path, contents).map_err(|error| error.to_string()));
+
+    if let Err(error) = result {
+        error!(path, %error, "Failed to save {name}");
+    }
+}
+
+// Saved files only last for the session on the web
+#[cfg(target_arch = "wasm32")]
+pub fn load_ron<T: DeserializeOwned + Default>(_path: &str, _name: &str) -> T {
+    T::default()
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn save_ron<T: Serialize>(_path: &str, _name: &str, _value: &T) {}
@@ -2,7 +2,12 @@ use std::{f32::consts::PI, time::Duration};
 
 use bevy::prelude::*;
 
-use crate::player::bullet::SpawnPlayerBulletCommand;
+use crate::{
+    Team,
+    bullet::{BULLET_LIFETIME, BULLET_SPEED, SpawnBulletCommand, TowerHit},
+    controls::Actions,
+    player::PlayerInput,
+};
 
 #[derive(Component)]
 pub struct PlayerGun {
@@ -11,6 +16,10 @@ pub struct PlayerGun {
     pub cooldown: Timer,
 }
 
+/// Emitted each time the player's gun fires.
+#[derive(Event)]
+pub struct GunFiredEvent;
+
 impl Default for PlayerGun {
     fn default() -> Self {
         PlayerGun {
@@ -20,14 +29,26 @@ impl Default for PlayerGun {
     }
 }
 
-pub fn update_gun_direction(mut q_gun: Query<&mut PlayerGun>, q_window: Query<&Window>) -> Result {
+pub fn update_gun_direction(mut input: ResMut<PlayerInput>, q_window: Query<&Window>) -> Result {
     let window = q_window.single()?;
     if let Some(cursor_pos) = window.cursor_position() {
-        let mut gun = q_gun.single_mut()?;
         let center = window.size() / 2.0;
         let direction = (cursor_pos - center).normalize_or_zero();
-        gun.angle = direction.y.atan2(direction.x);
+        input.gun_angle = direction.y.atan2(direction.x);
+    }
+    Ok(())
+}
+
+/// Aims with the right stick, matching the screen space angle used for the cursor.
+pub fn update_gun_stick_direction(actions: Actions, mut input: ResMut<PlayerInput>) {
+    if let Some(stick) = actions.right_stick() {
+        input.gun_angle = (-stick.y).atan2(stick.x);
     }
+}
+
+pub fn aim_gun(input: Res<PlayerInput>, mut q_gun: Query<&mut PlayerGun>) -> Result {
+    let mut gun = q_gun.single_mut()?;
+    gun.angle = input.gun_angle;
     Ok(())
 }
 
@@ -37,7 +58,11 @@ pub fn update_gun_cooldown(time: Res<Time>, mut q_gun: Query<&mut PlayerGun>) ->
     Ok(())
 }
 
-pub fn fire_gun(mut commands: Commands, mut q_gun: Query<(&mut PlayerGun, &Transform)>) -> Result {
+pub fn fire_gun(
+    mut commands: Commands,
+    mut q_gun: Query<(&mut PlayerGun, &Transform)>,
+    mut evw_gun_fired: EventWriter<GunFiredEvent>,
+) -> Result {
     let (mut gun, gun_transform) = q_gun.single_mut()?;
 
     if !gun.cooldown.finished() {
@@ -50,10 +75,16 @@ pub fn fire_gun(mut commands: Commands, mut q_gun: Query<(&mut PlayerGun, &Trans
         .with_rotation(Quat::from_axis_angle(Vec3::Y, -PI / 2.0 + -gun.angle));
     transform.translation = transform.translation + transform.forward().as_vec3() * 1.5;
 
-    commands.queue(SpawnPlayerBulletCommand {
+    commands.queue(SpawnBulletCommand {
+        team: Team::Player,
         transform,
+        damage: 1,
+        speed: BULLET_SPEED,
+        lifetime: BULLET_LIFETIME,
+        tower_hit: TowerHit::Trigger,
         trigger_history: Vec::new(),
     });
+    evw_gun_fired.write(GunFiredEvent);
 
     Ok(())
 }
@@ -0,0 +1,39 @@
+use bevy::prelude::*;
+
+use crate::controls::{Actions, InputAction};
+
+/// The player's input for the current fixed tick.
+///
+/// Gameplay systems only read this, it's written from live input or from a replay.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq)]
+pub struct PlayerInput {
+    /// Movement relative to the camera, x is right and y is down.
+    pub movement: Vec2,
+    /// The angle of the gun in radians (in the range [-π, +π]).
+    pub gun_angle: f32,
+    /// Whether the gun should fire.
+    pub fire: bool,
+}
+
+pub fn read_live_input(actions: Actions, mut input: ResMut<PlayerInput>) {
+    let stick = actions
+        .left_stick()
+        .map(|stick| Vec2::new(stick.x, -stick.y))
+        .unwrap_or_default();
+    input.movement = stick
+        + Vec2::new(
+            -(actions.pressed(InputAction::MoveLeft) as i32
+                - actions.pressed(InputAction::MoveRight) as i32) as f32,
+            -(actions.pressed(InputAction::MoveUp) as i32
+                - actions.pressed(InputAction::MoveDown) as i32) as f32,
+        );
+    input.fire = actions.pressed(InputAction::Fire);
+}
+
+pub fn fire_pressed(input: Res<PlayerInput>) -> bool {
+    input.fire
+}
+
+pub fn reset_input(mut input: ResMut<PlayerInput>) {
+    *input = PlayerInput::default();
+}
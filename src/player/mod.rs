@@ -1,41 +1,63 @@
-use bevy::{input::common_conditions::input_pressed, prelude::*};
+use bevy::prelude::*;
 
-mod bullet;
 mod gun;
+mod input;
 mod movement;
 mod spawn;
 
-pub use bullet::SpawnPlayerBulletCommand;
-pub use gun::PlayerGun;
+pub use gun::{GunFiredEvent, PlayerGun};
+pub use input::PlayerInput;
 
-use crate::{AppState, GameState, PlayerTeam, arena_index::ArenaHex};
+use crate::{
+    AppState, GameState, PlayerTeam, arena_index::ArenaHex, interpolation::InterpolateTranslation,
+    replay::is_replaying, rng::RngSet,
+};
 
 pub struct PlayerPlugin;
 
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
-        app
+        app.init_resource::<PlayerInput>()
+            .add_event::<GunFiredEvent>()
             // Setup
             .add_systems(
                 OnEnter(AppState::InGame),
-                spawn::setup_player.in_set(PlayerSet),
+                (input::reset_input, spawn::setup_player.after(RngSet)).in_set(PlayerSet),
             )
             // Cleanup
             .add_systems(
                 OnExit(AppState::InGame),
-                (spawn::cleanup_player, bullet::cleanup_bullets).in_set(PlayerSet),
+                spawn::cleanup_player.in_set(PlayerSet),
             )
             // Update
             .add_systems(
                 Update,
                 (
-                    movement::player_movement,
                     gun::update_gun_direction.run_if(on_event::<CursorMoved>),
+                    gun::update_gun_stick_direction,
+                )
+                    .run_if(not(is_replaying))
+                    .in_set(PlayerSet)
+                    .run_if(in_state(AppState::InGame))
+                    .run_if(in_state(GameState::Running)),
+            )
+            .add_systems(
+                FixedUpdate,
+                input::read_live_input
+                    .run_if(not(is_replaying))
+                    .in_set(PlayerInputSet)
+                    .run_if(in_state(AppState::InGame))
+                    .run_if(in_state(GameState::Running)),
+            )
+            .add_systems(
+                FixedUpdate,
+                (
+                    movement::player_movement,
                     gun::update_gun_cooldown,
-                    gun::fire_gun.run_if(input_pressed(MouseButton::Left)),
-                    bullet::update_bullets,
-                    (bullet::check_enemy_collision, bullet::check_tower_collision)
-                        .after(bullet::update_bullets),
+                    gun::aim_gun,
+                    gun::fire_gun
+                        .run_if(input::fire_pressed)
+                        .after(gun::aim_gun),
                 )
                     .in_set(PlayerSet)
                     .run_if(in_state(AppState::InGame))
@@ -47,8 +69,12 @@ impl Plugin for PlayerPlugin {
 #[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct PlayerSet;
 
+/// Systems that write `PlayerInput` for the current fixed tick, runs before `PlayerSet`.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PlayerInputSet;
+
 #[derive(Component)]
-#[require(PlayerTeam, ArenaHex, Transform, Visibility)]
+#[require(PlayerTeam, ArenaHex, InterpolateTranslation, Transform, Visibility)]
 pub struct Player;
 
 #[derive(Component)]
@@ -6,30 +6,30 @@ use crate::{
     arena_index::{ArenaHex, ArenaIndex},
 };
 
-use super::{Player, PlayerCamera};
+use super::{Player, PlayerCamera, PlayerInput};
 
 const MOVE_SPEED: f32 = 10.0;
 
 pub fn player_movement(
-    key_input: Res<ButtonInput<KeyCode>>,
+    input: Res<PlayerInput>,
     time: Res<Time>,
     arena: Res<Arena>,
     arena_index: Res<ArenaIndex>,
     player: Single<(&mut Transform, &ArenaHex), With<Player>>,
-    camera_transform: Single<&Transform, (With<PlayerCamera>, Without<Player>)>,
+    camera_transform: Option<Single<&Transform, (With<PlayerCamera>, Without<Player>)>>,
 ) {
-    let input = Vec2::new(
-        -(key_input.pressed(KeyCode::KeyA) as i32 - key_input.pressed(KeyCode::KeyD) as i32) as f32,
-        -(key_input.pressed(KeyCode::KeyW) as i32 - key_input.pressed(KeyCode::KeyS) as i32) as f32,
-    )
-    .normalize_or_zero();
+    // Sticks can move slower than full speed
+    let input = input.movement.clamp_length_max(1.0);
 
     if input.length() == 0.0 {
         return;
     }
 
-    // Get the player camera and rotate input to be relative to the camera
-    let camera_yaw = -camera_transform.rotation.to_euler(EulerRot::YXZ).0;
+    // Get the player camera and rotate input to be relative to the camera (there is no camera when
+    // running headless)
+    let camera_yaw = camera_transform
+        .map(|transform| -transform.rotation.to_euler(EulerRot::YXZ).0)
+        .unwrap_or(0.0);
     let rotated_input = Vec2::new(
         input.x * camera_yaw.cos() - input.y * camera_yaw.sin(),
         input.x * camera_yaw.sin() + input.y * camera_yaw.cos(),
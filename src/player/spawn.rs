@@ -2,58 +2,62 @@ use bevy::prelude::*;
 
 use crate::{
     AppState,
+    difficulty::Difficulty,
     force::ForceEmitter,
+    game_assets::GameAssets,
     health::{DiedEvent, Health},
 };
 
 use super::{Player, PlayerCamera, PlayerGun};
 
+/// Spawns the player, the camera, body and light are only spawned when `GameAssets` is available
+/// (i.e. not when running headless).
 pub fn setup_player(
     mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
+    game_assets: Option<Res<GameAssets>>,
+    difficulty: Res<Difficulty>,
 ) {
-    commands
-        .spawn((
-            Player,
-            PlayerGun::default(),
-            Health::new(3),
-            ForceEmitter {
-                radius: 5.0,
-                strength: 20.0,
+    let mut player = commands.spawn((
+        Player,
+        PlayerGun::default(),
+        Health::new(difficulty.player_health()),
+        ForceEmitter {
+            radius: 5.0,
+            strength: 20.0,
+        },
+    ));
+    player.observe(player_death_observer);
+
+    let Some(game_assets) = game_assets else {
+        return;
+    };
+
+    player.insert(children![
+        // Camera
+        (
+            PlayerCamera,
+            Camera {
+                hdr: true,
+                ..default()
+            },
+            Transform::from_xyz(0.0, 30.0, 5.0).looking_at(Vec3::ZERO, Vec3::Y),
+        ),
+        // Player body
+        (
+            Mesh3d(game_assets.player_mesh.clone()),
+            MeshMaterial3d(game_assets.player_material.clone()),
+            Transform::from_xyz(0.0, 1.0, 0.0),
+        ),
+        // Light
+        (
+            PointLight {
+                range: 30.0,
+                intensity: 8_000_000.0,
+                ..default()
             },
-            children![
-                // Camera
-                (
-                    PlayerCamera,
-                    Camera {
-                        hdr: true,
-                        ..default()
-                    },
-                    Transform::from_xyz(0.0, 30.0, 5.0).looking_at(Vec3::ZERO, Vec3::Y),
-                ),
-                // Player body
-                (
-                    Mesh3d(meshes.add(Cylinder::new(0.5, 0.2))),
-                    MeshMaterial3d(materials.add(StandardMaterial {
-                        base_color: Color::hsl(100.0, 0.7, 0.3),
-                        perceptual_roughness: 1.0,
-                        ..default()
-                    })),
-                    Transform::from_xyz(0.0, 1.0, 0.0),
-                ),
-                // Light
-                (
-                    PointLight {
-                        range: 30.0,
-                        intensity: 8_000_000.0,
-                        ..default()
-                    },
-                    Transform::from_xyz(0.0, 5.0, 0.0),
-                ),
-            ],
-        ))
-        .observe(player_death_observer);
+            Transform::from_xyz(0.0, 5.0, 0.0),
+        ),
+    ]);
 }
 
 pub fn cleanup_player(mut commands: Commands, q_player: Query<Entity, With<Player>>) -> Result {
@@ -1,10 +1,12 @@
 use bevy::prelude::*;
 use hexx::Hex;
 
-use crate::arena::Arena;
+use crate::{AppState, GameState, arena::Arena, controls::Actions, player::PlayerCamera};
 
 const HIT_PLANE_HEIGHT: f32 = 0.5;
 const HIT_PLANE_SIZE: f32 = 1000.0;
+/// Seconds between the pointer moving to the next hex while a stick is held.
+const STICK_REPEAT_DELAY: f32 = 0.15;
 
 pub struct PointerTrackingPlugin;
 
@@ -13,7 +15,13 @@ impl Plugin for PointerTrackingPlugin {
         app.init_resource::<PointerPosition>()
             .add_event::<PointerMovedEvent>()
             .add_event::<PointerChangedHexEvent>()
-            .add_systems(Startup, setup_hit_plane);
+            .add_systems(Startup, setup_hit_plane)
+            .add_systems(
+                Update,
+                move_pointer_with_stick
+                    .run_if(in_state(AppState::InGame))
+                    .run_if(in_state(GameState::Building)),
+            );
     }
 }
 
@@ -29,6 +37,7 @@ pub struct PointerMovedEvent {
     pub new_pos: Vec3,
 }
 
+/// Fired whenever the pointer moves to a different hex, from the mouse or a gamepad stick.
 #[derive(Event)]
 pub struct PointerChangedHexEvent {
     pub old_hex: Hex,
@@ -79,3 +88,64 @@ fn pointer_move_observer(
         }
     }
 }
+
+/// Steps the pointer to the neighbouring hex the left stick points at, so towers can be placed
+/// without a mouse.
+fn move_pointer_with_stick(
+    time: Res<Time>,
+    actions: Actions,
+    arena: Res<Arena>,
+    mut pointer_pos: ResMut<PointerPosition>,
+    camera_transform: Option<Single<&Transform, With<PlayerCamera>>>,
+    mut evw_pointer_changed_hex: EventWriter<PointerChangedHexEvent>,
+    mut repeat_delay: Local<f32>,
+) {
+    let Some(stick) = actions.left_stick() else {
+        *repeat_delay = 0.0;
+        return;
+    };
+
+    if *repeat_delay > 0.0 {
+        *repeat_delay -= time.delta_secs();
+        return;
+    }
+
+    // Rotate the stick to be relative to the camera, in the same way as player movement
+    let input = Vec2::new(stick.x, -stick.y).normalize();
+    let camera_yaw = camera_transform
+        .map(|transform| -transform.rotation.to_euler(EulerRot::YXZ).0)
+        .unwrap_or(0.0);
+    let direction = Vec2::new(
+        input.x * camera_yaw.cos() - input.y * camera_yaw.sin(),
+        input.x * camera_yaw.sin() + input.y * camera_yaw.cos(),
+    );
+
+    let current_pos = arena.layout.hex_to_world_pos(pointer_pos.hex);
+    let Some(hex) = pointer_pos
+        .hex
+        .all_neighbors()
+        .into_iter()
+        .filter(|hex| hex.unsigned_distance_to(Hex::ZERO) <= Arena::RADIUS)
+        .max_by(|a, b| {
+            let a_dot = (arena.layout.hex_to_world_pos(*a) - current_pos)
+                .normalize()
+                .dot(direction);
+            let b_dot = (arena.layout.hex_to_world_pos(*b) - current_pos)
+                .normalize()
+                .dot(direction);
+            a_dot.total_cmp(&b_dot)
+        })
+    else {
+        return;
+    };
+
+    *repeat_delay = STICK_REPEAT_DELAY;
+
+    let pos = arena.layout.hex_to_world_pos(hex);
+    pointer_pos.pos = Vec3::new(pos.x, HIT_PLANE_HEIGHT, pos.y);
+    evw_pointer_changed_hex.write(PointerChangedHexEvent {
+        old_hex: pointer_pos.hex,
+        new_hex: hex,
+    });
+    pointer_pos.hex = hex;
+}
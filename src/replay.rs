@@ -0,0 +1,580 @@
+use std::{fmt, path::Path};
+
+use bevy::prelude::*;
+use hexx::Hex;
+
+use crate::{
+    AppState, GameState,
+    building::{BuildTowerCommand, PickUpTowerCommand},
+    difficulty::Difficulty,
+    player::{PlayerInput, PlayerInputSet, PlayerSet},
+    rng::{NextRunSeed, RngSet, RunRng},
+    score::{PlayerScore, ScoreSet},
+    waves::{ChooseRewardCommand, GameMode},
+};
+
+const REPLAY_MAGIC: &[u8; 4] = b"CRRP";
+const REPLAY_VERSION: u8 = 1;
+#[cfg(not(target_arch = "wasm32"))]
+const REPLAY_DIRECTORY: &str = "replays";
+/// The most frames a replay can hold, four hours at the default 64Hz fixed timestep. Anything
+/// longer is a corrupt file that would otherwise allocate gigabytes.
+const MAX_REPLAY_FRAMES: usize = 64 * 60 * 60 * 4;
+
+/// Records every run, and plays back a run when `ReplayPlayback` is inserted.
+pub struct ReplayPlugin {
+    /// Whether finished runs are saved to the replays directory.
+    pub save_replays: bool,
+}
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(ReplaySettings {
+            save_replays: self.save_replays,
+        })
+        .add_systems(
+            OnEnter(AppState::InGame),
+            (
+                seed_from_playback.before(RngSet),
+                start_recording.after(RngSet).run_if(not(is_replaying)),
+            ),
+        )
+        .add_systems(OnEnter(AppState::GameOver), finish_replay)
+        .add_systems(OnEnter(AppState::Menu), discard_replay)
+        .add_systems(
+            FixedUpdate,
+            (
+                apply_playback.run_if(is_replaying).in_set(PlayerInputSet),
+                record_input
+                    .run_if(resource_exists::<ReplayRecorder>)
+                    .after(PlayerInputSet)
+                    .before(PlayerSet),
+            )
+                .run_if(in_state(AppState::InGame))
+                .run_if(in_state(GameState::Running)),
+        )
+        .add_systems(
+            Update,
+            choose_playback_reward
+                .run_if(is_replaying)
+                .run_if(in_state(AppState::InGame))
+                .run_if(in_state(GameState::RewardSelect)),
+        );
+    }
+}
+
+#[derive(Resource)]
+struct ReplaySettings {
+    save_replays: bool,
+}
+
+/// Everything needed to reproduce a run: the seed, the input for every fixed tick and the
+/// player's decisions.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Replay {
+    pub seed: u64,
+    pub mode: GameMode,
+    pub difficulty: Difficulty,
+    /// The player's input for each fixed tick the game was running.
+    pub frames: Vec<PlayerInput>,
+    /// Decisions made while the game wasn't running, in the order they were made.
+    pub actions: Vec<ReplayEvent>,
+    /// The final score, used to verify the replay reproduced the run.
+    pub score: Option<u128>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReplayEvent {
+    /// The number of fixed ticks that had run when the action was taken.
+    pub tick: u64,
+    pub action: ReplayAction,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReplayAction {
+    /// See `BuildTowerCommand`.
+    BuildTower {
+        index: usize,
+        hex: Hex,
+        rotation: u8,
+    },
+    /// See `ChooseRewardCommand`.
+    ChooseReward { option: usize },
+    /// See `PickUpTowerCommand`.
+    PickUpTower { hex: Hex },
+}
+
+#[derive(Debug)]
+pub enum ReplayError {
+    Io(std::io::Error),
+    InvalidHeader,
+    UnsupportedVersion(u8),
+    UnexpectedEnd,
+    InvalidMode(u8),
+    InvalidDifficulty(u8),
+    InvalidAction(u8),
+    TooLong,
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::Io(error) => write!(f, "failed to read replay: {error}"),
+            ReplayError::InvalidHeader => write!(f, "not a replay file"),
+            ReplayError::UnsupportedVersion(version) => {
+                write!(f, "unsupported replay version {version}")
+            }
+            ReplayError::UnexpectedEnd => write!(f, "replay file ended unexpectedly"),
+            ReplayError::InvalidMode(mode) => write!(f, "unknown game mode {mode}"),
+            ReplayError::InvalidDifficulty(difficulty) => {
+                write!(f, "unknown difficulty {difficulty}")
+            }
+            ReplayError::InvalidAction(kind) => write!(f, "unknown replay action {kind}"),
+            ReplayError::TooLong => write!(f, "replay is longer than {MAX_REPLAY_FRAMES} frames"),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+impl Replay {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ReplayError> {
+        let bytes = std::fs::read(path).map_err(ReplayError::Io)?;
+        Self::from_bytes(&bytes)
+    }
+
+    /// Encodes the replay, runs of identical input frames are only stored once.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(REPLAY_MAGIC);
+        bytes.push(REPLAY_VERSION);
+        bytes.extend_from_slice(&self.seed.to_le_bytes());
+        bytes.push(match self.mode {
+            GameMode::Standard => 0,
+            GameMode::Endless => 1,
+        });
+        bytes.push(match self.difficulty {
+            Difficulty::Easy => 0,
+            Difficulty::Normal => 1,
+            Difficulty::Hard => 2,
+            Difficulty::Nightmare => 3,
+        });
+        match self.score {
+            Some(score) => {
+                bytes.push(1);
+                bytes.extend_from_slice(&score.to_le_bytes());
+            }
+            None => bytes.push(0),
+        }
+
+        // Run-length encode the frames
+        let mut runs: Vec<(u32, PlayerInput)> = Vec::new();
+        for frame in &self.frames {
+            match runs.last_mut() {
+                Some((count, input)) if input == frame => *count += 1,
+                _ => runs.push((1, *frame)),
+            }
+        }
+        bytes.extend_from_slice(&(runs.len() as u32).to_le_bytes());
+        for (count, input) in runs {
+            bytes.extend_from_slice(&count.to_le_bytes());
+            bytes.extend_from_slice(&input.movement.x.to_le_bytes());
+            bytes.extend_from_slice(&input.movement.y.to_le_bytes());
+            bytes.extend_from_slice(&input.gun_angle.to_le_bytes());
+            bytes.push(input.fire as u8);
+        }
+
+        bytes.extend_from_slice(&(self.actions.len() as u32).to_le_bytes());
+        for event in &self.actions {
+            bytes.extend_from_slice(&event.tick.to_le_bytes());
+            match event.action {
+                ReplayAction::BuildTower {
+                    index,
+                    hex,
+                    rotation,
+                } => {
+                    bytes.push(0);
+                    bytes.extend_from_slice(&(index as u32).to_le_bytes());
+                    bytes.extend_from_slice(&hex.x.to_le_bytes());
+                    bytes.extend_from_slice(&hex.y.to_le_bytes());
+                    bytes.push(rotation);
+                }
+                ReplayAction::ChooseReward { option } => {
+                    bytes.push(1);
+                    bytes.extend_from_slice(&(option as u32).to_le_bytes());
+                }
+                ReplayAction::PickUpTower { hex } => {
+                    bytes.push(2);
+                    bytes.extend_from_slice(&hex.x.to_le_bytes());
+                    bytes.extend_from_slice(&hex.y.to_le_bytes());
+                }
+            }
+        }
+
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ReplayError> {
+        let mut reader = ByteReader { bytes, position: 0 };
+
+        if &reader.take::<4>()? != REPLAY_MAGIC {
+            return Err(ReplayError::InvalidHeader);
+        }
+        let [version] = reader.take::<1>()?;
+        if version != REPLAY_VERSION {
+            return Err(ReplayError::UnsupportedVersion(version));
+        }
+
+        let seed = u64::from_le_bytes(reader.take()?);
+        let mode = match reader.take::<1>()? {
+            [0] => GameMode::Standard,
+            [1] => GameMode::Endless,
+            [mode] => return Err(ReplayError::InvalidMode(mode)),
+        };
+        let difficulty = match reader.take::<1>()? {
+            [0] => Difficulty::Easy,
+            [1] => Difficulty::Normal,
+            [2] => Difficulty::Hard,
+            [3] => Difficulty::Nightmare,
+            [difficulty] => return Err(ReplayError::InvalidDifficulty(difficulty)),
+        };
+        let score = match reader.take::<1>()? {
+            [0] => None,
+            _ => Some(u128::from_le_bytes(reader.take()?)),
+        };
+
+        let mut frames = Vec::new();
+        let runs = u32::from_le_bytes(reader.take()?);
+        for _ in 0..runs {
+            let count = u32::from_le_bytes(reader.take()?);
+            let input = PlayerInput {
+                movement: Vec2::new(
+                    f32::from_le_bytes(reader.take()?),
+                    f32::from_le_bytes(reader.take()?),
+                ),
+                gun_angle: f32::from_le_bytes(reader.take()?),
+                fire: reader.take::<1>()? != [0],
+            };
+            if frames.len() + count as usize > MAX_REPLAY_FRAMES {
+                return Err(ReplayError::TooLong);
+            }
+            frames.extend(std::iter::repeat_n(input, count as usize));
+        }
+
+        let mut actions = Vec::new();
+        let action_count = u32::from_le_bytes(reader.take()?);
+        for _ in 0..action_count {
+            let tick = u64::from_le_bytes(reader.take()?);
+            let action = match reader.take::<1>()? {
+                [0] => ReplayAction::BuildTower {
+                    index: u32::from_le_bytes(reader.take()?) as usize,
+                    hex: Hex::new(
+                        i32::from_le_bytes(reader.take()?),
+                        i32::from_le_bytes(reader.take()?),
+                    ),
+                    rotation: reader.take::<1>()?[0],
+                },
+                [1] => ReplayAction::ChooseReward {
+                    option: u32::from_le_bytes(reader.take()?) as usize,
+                },
+                [2] => ReplayAction::PickUpTower {
+                    hex: Hex::new(
+                        i32::from_le_bytes(reader.take()?),
+                        i32::from_le_bytes(reader.take()?),
+                    ),
+                },
+                [kind] => return Err(ReplayError::InvalidAction(kind)),
+            };
+            actions.push(ReplayEvent { tick, action });
+        }
+
+        Ok(Replay {
+            seed,
+            mode,
+            difficulty,
+            frames,
+            actions,
+            score,
+        })
+    }
+}
+
+struct ByteReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl ByteReader<'_> {
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], ReplayError> {
+        let bytes = self
+            .bytes
+            .get(self.position..self.position + N)
+            .ok_or(ReplayError::UnexpectedEnd)?;
+        self.position += N;
+        Ok(bytes.try_into().unwrap())
+    }
+}
+
+/// The replay of the current run, while it's being recorded.
+#[derive(Resource)]
+pub struct ReplayRecorder {
+    pub replay: Replay,
+}
+
+impl ReplayRecorder {
+    /// Records an action taken before the next fixed tick.
+    pub fn record(&mut self, action: ReplayAction) {
+        let tick = self.replay.frames.len() as u64;
+        self.replay.actions.push(ReplayEvent { tick, action });
+    }
+}
+
+/// Insert to play back a replay instead of reading live input, the run starts with the replay's
+/// seed and live input is ignored until the run ends.
+#[derive(Resource)]
+pub struct ReplayPlayback {
+    replay: Replay,
+    /// The number of fixed ticks played back.
+    tick: u64,
+    /// The index of the next action in `Replay::actions` to apply.
+    next_action: usize,
+}
+
+impl ReplayPlayback {
+    pub fn new(replay: Replay) -> Self {
+        Self {
+            replay,
+            tick: 0,
+            next_action: 0,
+        }
+    }
+
+    /// Whether every recorded tick has been played back.
+    pub fn is_finished(&self) -> bool {
+        self.tick as usize >= self.replay.frames.len()
+    }
+}
+
+/// Run condition that's true while a replay is being played back.
+pub fn is_replaying(playback: Option<Res<ReplayPlayback>>) -> bool {
+    playback.is_some()
+}
+
+fn seed_from_playback(
+    mut next_seed: ResMut<NextRunSeed>,
+    mut game_mode: ResMut<GameMode>,
+    mut difficulty: ResMut<Difficulty>,
+    playback: Option<ResMut<ReplayPlayback>>,
+) {
+    let Some(mut playback) = playback else {
+        return;
+    };
+
+    next_seed.0 = Some(playback.replay.seed);
+    *game_mode = playback.replay.mode;
+    *difficulty = playback.replay.difficulty;
+    playback.tick = 0;
+    playback.next_action = 0;
+}
+
+fn start_recording(
+    mut commands: Commands,
+    run_rng: Res<RunRng>,
+    game_mode: Res<GameMode>,
+    difficulty: Res<Difficulty>,
+) {
+    commands.insert_resource(ReplayRecorder {
+        replay: Replay {
+            seed: run_rng.seed(),
+            mode: *game_mode,
+            difficulty: *difficulty,
+            ..default()
+        },
+    });
+}
+
+fn record_input(input: Res<PlayerInput>, mut recorder: ResMut<ReplayRecorder>) {
+    recorder.replay.frames.push(*input);
+}
+
+fn apply_playback(
+    mut commands: Commands,
+    mut playback: ResMut<ReplayPlayback>,
+    mut input: ResMut<PlayerInput>,
+) {
+    let tick = playback.tick;
+    *input = playback
+        .replay
+        .frames
+        .get(tick as usize)
+        .copied()
+        .unwrap_or_default();
+
+    // Apply any buildings built or picked up before this tick
+    while let Some(event) = playback.replay.actions.get(playback.next_action) {
+        if event.tick > tick {
+            break;
+        }
+        match event.action {
+            ReplayAction::BuildTower {
+                index,
+                hex,
+                rotation,
+            } => commands.queue(BuildTowerCommand {
+                index,
+                hex,
+                rotation,
+            }),
+            ReplayAction::PickUpTower { hex } => commands.queue(PickUpTowerCommand { hex }),
+            // Rewards are chosen once the game is in `GameState::RewardSelect`
+            ReplayAction::ChooseReward { .. } => break,
+        }
+        playback.next_action += 1;
+    }
+
+    playback.tick += 1;
+}
+
+fn choose_playback_reward(mut commands: Commands, mut playback: ResMut<ReplayPlayback>) {
+    let Some(event) = playback.replay.actions.get(playback.next_action) else {
+        return;
+    };
+    let ReplayAction::ChooseReward { option } = event.action else {
+        warn!(action=?event.action, "Replay expected a reward choice");
+        return;
+    };
+
+    commands.queue(ChooseRewardCommand { option });
+    playback.next_action += 1;
+}
+
+pub fn finish_replay(
+    mut commands: Commands,
+    settings: Res<ReplaySettings>,
+    score: Res<PlayerScore>,
+    recorder: Option<ResMut<ReplayRecorder>>,
+    playback: Option<Res<ReplayPlayback>>,
+) {
+    if let Some(mut recorder) = recorder {
+        recorder.replay.score = Some(score.score);
+        if settings.save_replays {
+            save_replay(&recorder.replay);
+        }
+        commands.remove_resource::<ReplayRecorder>();
+    }
+
+    if let Some(playback) = playback {
+        match playback.replay.score {
+            Some(expected) if expected == score.score => {
+                info!(score=?score.score, "Replay verified");
+            }
+            Some(expected) => {
+                warn!(expected=?expected, actual=?score.score, "Replay diverged from the recorded run");
+            }
+            None => {}
+        }
+        commands.remove_resource::<ReplayPlayback>();
+    }
+}
+
+fn discard_replay(mut commands: Commands) {
+    commands.remove_resource::<ReplayRecorder>();
+    commands.remove_resource::<ReplayPlayback>();
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn save_replay(replay: &Replay) {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default();
+    let directory = Path::new(REPLAY_DIRECTORY);
+    let path = directory.join(format!("{}-{}.replay", replay.seed, timestamp));
+
+    match std::fs::create_dir_all(directory).and_then(|_| std::fs::write(&path, replay.to_bytes()))
+    {
+        Ok(()) => info!(?path, "Saved replay"),
+        Err(error) => error!(?path, %error, "Failed to save replay"),
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn save_replay(_replay: &Replay) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replay_round_trip() {
+        let idle = PlayerInput::default();
+        let firing = PlayerInput {
+            movement: Vec2::new(1.0, -1.0),
+            gun_angle: 0.5,
+            fire: true,
+        };
+        let replay = Replay {
+            seed: 42,
+            mode: GameMode::Endless,
+            difficulty: Difficulty::Hard,
+            frames: vec![idle, idle, idle, firing, firing, idle],
+            actions: vec![
+                ReplayEvent {
+                    tick: 3,
+                    action: ReplayAction::BuildTower {
+                        index: 1,
+                        hex: Hex::new(-2, 5),
+                        rotation: 4,
+                    },
+                },
+                ReplayEvent {
+                    tick: 5,
+                    action: ReplayAction::PickUpTower {
+                        hex: Hex::new(-2, 5),
+                    },
+                },
+                ReplayEvent {
+                    tick: 6,
+                    action: ReplayAction::ChooseReward { option: 0 },
+                },
+            ],
+            score: Some(1234),
+        };
+
+        assert_eq!(Replay::from_bytes(&replay.to_bytes()).unwrap(), replay);
+    }
+
+    #[test]
+    fn replay_too_long() {
+        let replay = Replay {
+            seed: 1,
+            mode: GameMode::Standard,
+            difficulty: Difficulty::Normal,
+            frames: vec![PlayerInput::default()],
+            actions: Vec::new(),
+            score: None,
+        };
+        let mut bytes = replay.to_bytes();
+        // Overwrite the length of the only run of frames
+        bytes[20..24].copy_from_slice(&u32::MAX.to_le_bytes());
+
+        assert!(matches!(
+            Replay::from_bytes(&bytes),
+            Err(ReplayError::TooLong)
+        ));
+    }
+
+    #[test]
+    fn replay_invalid_header() {
+        assert!(matches!(
+            Replay::from_bytes(b"nope"),
+            Err(ReplayError::InvalidHeader)
+        ));
+        assert!(matches!(
+            Replay::from_bytes(b"CR"),
+            Err(ReplayError::UnexpectedEnd)
+        ));
+    }
+}
@@ -1,8 +1,12 @@
 use bevy::prelude::*;
 
 use crate::{
-    AppState, GameState, building::BuildingSettings, game_assets::GameAssets, tower::TowerKind,
-    waves::WaveManager,
+    AppState, GameState,
+    building::{BuildingSettings, CollectedTower},
+    game_assets::{GameAssets, tower_icon_color},
+    replay::is_replaying,
+    tower::{TowerList, TowerRegistry},
+    waves::{ChooseRewardCommand, WaveManager, roll_reward_options},
 };
 
 const GREEN: Color = Color::srgb(0.15, 0.62, 0.33);
@@ -12,14 +16,18 @@ pub struct RewardSelectPlugin;
 
 impl Plugin for RewardSelectPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(GameState::RewardSelect), setup_reward_select)
-            .add_systems(OnExit(GameState::RewardSelect), cleanup_reward_select)
-            .add_systems(
-                Update,
-                reward_button_interactions
-                    .run_if(in_state(AppState::InGame))
-                    .run_if(in_state(GameState::RewardSelect)),
-            );
+        app.add_systems(
+            OnEnter(GameState::RewardSelect),
+            setup_reward_select.after(roll_reward_options),
+        )
+        .add_systems(OnExit(GameState::RewardSelect), cleanup_reward_select)
+        .add_systems(
+            Update,
+            reward_button_interactions
+                .run_if(not(is_replaying))
+                .run_if(in_state(AppState::InGame))
+                .run_if(in_state(GameState::RewardSelect)),
+        );
     }
 }
 
@@ -29,19 +37,20 @@ struct RewardSelectUi;
 #[derive(Component)]
 #[require(Button)]
 struct RewardButton {
-    reward: TowerKind,
+    /// The index of the reward in `WaveManager::reward_options`.
+    option: usize,
 }
 
 fn reward_button_interactions(
+    mut commands: Commands,
     mut q_interaction: Query<(&RewardButton, &Interaction, &mut BorderColor, &mut Button)>,
-    mut next_game_state: ResMut<NextState<GameState>>,
-    mut building_settings: ResMut<BuildingSettings>,
 ) {
     for (reward_button, interaction, mut border_color, mut button) in q_interaction.iter_mut() {
         match *interaction {
             Interaction::Pressed => {
-                building_settings.towers.push(reward_button.reward.clone());
-                next_game_state.set(GameState::Running);
+                commands.queue(ChooseRewardCommand {
+                    option: reward_button.option,
+                });
                 button.set_changed();
             }
             Interaction::Hovered => {
@@ -59,9 +68,10 @@ fn setup_reward_select(
     mut commands: Commands,
     game_assets: Res<GameAssets>,
     wave_manager: Res<WaveManager>,
+    tower_registry: Res<TowerRegistry>,
+    tower_lists: Res<Assets<TowerList>>,
+    building_settings: Res<BuildingSettings>,
 ) {
-    let rewards = wave_manager.wave_reward().get_random_options();
-
     commands
         .spawn((
             RewardSelectUi,
@@ -106,22 +116,56 @@ fn setup_reward_select(
                     ..default()
                 })
                 .with_children(|parent| {
-                    for reward in rewards {
-                        let image = game_assets.tower_icons.get(&reward);
+                    for (option, reward) in wave_manager.reward_options().iter().enumerate() {
+                        let image = game_assets.tower_icons.get(reward);
+                        let mut name = tower_registry
+                            .get(&tower_lists, reward)
+                            .map(|definition| definition.name.clone())
+                            .unwrap_or_else(|| reward.to_string());
+                        // Rewards that merge with a collected tower are shown as its upgrade
+                        let level = building_settings
+                            .level_after_adding(&CollectedTower::new(reward.clone()));
+                        if level > 1 {
+                            name = format!("{name} (Upgrade to Lv {level})");
+                        }
+                        let color = tower_icon_color(level);
 
                         parent.spawn((
-                            RewardButton { reward: reward },
                             Node {
-                                width: Val::Px(60.0),
-                                height: Val::Px(60.0),
-                                padding: UiRect::all(Val::Px(10.0)),
-                                border: UiRect::all(Val::Px(4.0)),
+                                flex_direction: FlexDirection::Column,
+                                align_items: AlignItems::Center,
+                                row_gap: Val::Px(5.0),
                                 ..default()
                             },
-                            BackgroundColor(Color::srgb(0.1, 0.1, 0.1)),
-                            BorderColor(GREY),
-                            BorderRadius::all(Val::Px(6.0)),
-                            children![ImageNode { image, ..default() }],
+                            children![
+                                (
+                                    RewardButton { option },
+                                    Node {
+                                        width: Val::Px(60.0),
+                                        height: Val::Px(60.0),
+                                        padding: UiRect::all(Val::Px(10.0)),
+                                        border: UiRect::all(Val::Px(4.0)),
+                                        ..default()
+                                    },
+                                    BackgroundColor(Color::srgb(0.1, 0.1, 0.1)),
+                                    BorderColor(GREY),
+                                    BorderRadius::all(Val::Px(6.0)),
+                                    children![ImageNode {
+                                        image,
+                                        color,
+                                        ..default()
+                                    }],
+                                ),
+                                (
+                                    Text::new(name),
+                                    TextColor(color),
+                                    TextFont {
+                                        font: game_assets.audiowide_font.clone(),
+                                        font_size: 16.0,
+                                        ..default()
+                                    },
+                                )
+                            ],
                         ));
                     }
                 });
@@ -0,0 +1,67 @@
+use bevy::prelude::*;
+use rand::{SeedableRng, rngs::StdRng};
+
+use crate::AppState;
+
+pub struct RngPlugin;
+
+impl Plugin for RngPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<NextRunSeed>()
+            .insert_resource(RunRng::new(0))
+            .add_systems(OnEnter(AppState::InGame), setup_run_rng.in_set(RngSet));
+    }
+}
+
+/// Systems that seed the run's random number generators, anything using `RunRng` or the run's
+/// settings (e.g. `Difficulty`, which replays override) when entering `AppState::InGame` should
+/// run after this set.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RngSet;
+
+/// The seed to use for the next run, a random seed is used when this is `None`.
+///
+/// This is taken when the run starts, so it only applies to a single run.
+#[derive(Resource, Default)]
+pub struct NextRunSeed(pub Option<u64>);
+
+/// All randomness in a run comes from these generators so a run can be reproduced from its seed.
+///
+/// Each kind of decision has its own stream, so e.g. rolling extra cosmetic values doesn't change
+/// which enemies spawn or which rewards are offered.
+#[derive(Resource)]
+pub struct RunRng {
+    seed: u64,
+    /// Enemy spawn positions and archetypes.
+    pub spawning: StdRng,
+    /// Wave reward options.
+    pub rewards: StdRng,
+    /// Anything purely visual, e.g. arena column heights.
+    pub cosmetics: StdRng,
+}
+
+impl RunRng {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            spawning: StdRng::seed_from_u64(seed ^ 0x5350_4157_4E49_4E47),
+            rewards: StdRng::seed_from_u64(seed ^ 0x5245_5741_5244_5321),
+            cosmetics: StdRng::seed_from_u64(seed ^ 0x434F_534D_4554_4943),
+        }
+    }
+
+    /// The seed the run was started with.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+fn setup_run_rng(mut next_seed: ResMut<NextRunSeed>, mut run_rng: ResMut<RunRng>) {
+    // Random seeds are kept short so they're easy to share
+    let seed = next_seed
+        .0
+        .take()
+        .unwrap_or_else(|| rand::random::<u32>() as u64);
+    *run_rng = RunRng::new(seed);
+    info!(seed, "Starting run");
+}
@@ -0,0 +1,74 @@
+use std::{fmt, marker::PhantomData};
+
+use bevy::{
+    asset::{AssetLoader, LoadContext, io::Reader},
+    prelude::*,
+};
+use serde::de::DeserializeOwned;
+
+/// A list of definitions loaded from a RON file by `RonListLoader`, e.g. the waves or towers.
+pub trait RonList: Asset + DeserializeOwned {
+    /// What the list holds, used in error messages.
+    const NAME: &'static str;
+    /// The file extensions the loader is used for, e.g. `"waves.ron"`.
+    const EXTENSIONS: &'static [&'static str];
+
+    /// Checks the loaded list can be used, the error names the offending entry.
+    fn validate(&self) -> Result<(), String>;
+}
+
+/// Loads a `RonList` and validates it, lists that fail validation fail to load.
+pub struct RonListLoader<T>(PhantomData<fn() -> T>);
+
+impl<T> Default for RonListLoader<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+#[derive(Debug)]
+pub enum RonListLoaderError {
+    Io(&'static str, std::io::Error),
+    Parse(&'static str, ron::error::SpannedError),
+    Invalid(&'static str, String),
+}
+
+impl fmt::Display for RonListLoaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RonListLoaderError::Io(name, error) => write!(f, "could not read {name}: {error}"),
+            RonListLoaderError::Parse(name, error) => write!(f, "could not parse {name}: {error}"),
+            RonListLoaderError::Invalid(name, error) => write!(f, "invalid {name}: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for RonListLoaderError {}
+
+impl<T: RonList> AssetLoader for RonListLoader<T> {
+    type Asset = T;
+    type Settings = ();
+    type Error = RonListLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<T, RonListLoaderError> {
+        let mut bytes = Vec::new();
+        reader
+            .read_to_end(&mut bytes)
+            .await
+            .map_err(|error| RonListLoaderError::Io(T::NAME, error))?;
+        let list: T = ron::de::from_bytes(&bytes)
+            .map_err(|error| RonListLoaderError::Parse(T::NAME, error))?;
+        list.validate()
+            .map_err(|error| RonListLoaderError::Invalid(T::NAME, error))?;
+        Ok(list)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        T::EXTENSIONS
+    }
+}
@@ -1,6 +1,6 @@
 use bevy::prelude::*;
 
-use crate::{AppState, GameState};
+use crate::{AppState, GameState, difficulty::Difficulty};
 
 pub struct ScorePlugin;
 
@@ -11,11 +11,12 @@ impl Plugin for ScorePlugin {
             .init_resource::<PlayerScore>()
             .add_systems(OnEnter(AppState::InGame), reset_player_score)
             .add_systems(
-                Update,
+                FixedUpdate,
                 (
                     tick_combo.run_if(not(on_event::<IncreaseScoreEvent>)),
                     increase_score.run_if(on_event::<IncreaseScoreEvent>),
                 )
+                    .in_set(ScoreSet)
                     .run_if(in_state(AppState::InGame))
                     .run_if(in_state(GameState::Running)),
             );
@@ -84,13 +85,14 @@ fn tick_combo(
 fn increase_score(
     mut player_score: ResMut<PlayerScore>,
     mut evr_increase_score: EventReader<IncreaseScoreEvent>,
+    difficulty: Res<Difficulty>,
 ) {
     player_score.combo_timer.reset();
 
     for event in evr_increase_score.read() {
         let chain_mult = 1 + event.chain_length as u128;
         let combo_mult = player_score.combo.max(1);
-        player_score.score += event.score * chain_mult * combo_mult;
+        player_score.score += difficulty.scale_score(event.score * chain_mult * combo_mult);
         player_score.combo += 1;
 
         if player_score.combo > player_score.highest_combo {
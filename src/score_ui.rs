@@ -3,7 +3,7 @@ use bevy::prelude::*;
 use crate::{
     AppState,
     game_assets::GameAssets,
-    score::{ComboResetEvent, IncreaseScoreEvent, PlayerScore, ScoreSet},
+    score::{ComboResetEvent, IncreaseScoreEvent, PlayerScore},
 };
 
 pub struct ScoreUiPlugin;
@@ -14,9 +14,7 @@ impl Plugin for ScoreUiPlugin {
             .add_systems(OnExit(AppState::InGame), cleanup_score_ui)
             .add_systems(
                 Update,
-                update_score
-                    .after(ScoreSet)
-                    .run_if(on_event::<IncreaseScoreEvent>.or(on_event::<ComboResetEvent>)),
+                update_score.run_if(on_event::<IncreaseScoreEvent>.or(on_event::<ComboResetEvent>)),
             );
     }
 }
@@ -0,0 +1,414 @@
+use bevy::{
+    input::{
+        gamepad::GamepadButtonStateChangedEvent, keyboard::KeyboardInput, mouse::MouseButtonInput,
+    },
+    prelude::*,
+};
+
+use crate::{
+    AppState,
+    controls::{Binding, ControlBindings, InputAction},
+    game_assets::GameAssets,
+    sound::{AudioSettings, VolumeChannel},
+};
+
+const NORMAL_BUTTON: Color = Color::srgb(1.0, 1.0, 1.0);
+const HOVERED_BUTTON: Color = Color::srgb(0.0, 0.63, 1.0);
+const PRESSED_BUTTON: Color = Color::srgb(0.11, 0.3, 0.41);
+/// How much the volume buttons change a channel's volume.
+const VOLUME_STEP: f32 = 0.1;
+
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Rebinding>()
+            .add_systems(OnEnter(AppState::Settings), setup_settings)
+            .add_systems(OnExit(AppState::Settings), cleanup_settings)
+            .add_systems(
+                Update,
+                (
+                    // Runs first so the click that starts rebinding isn't used as the binding
+                    capture_binding,
+                    button_interaction,
+                    update_binding_labels.run_if(
+                        resource_changed::<ControlBindings>.or(resource_changed::<Rebinding>),
+                    ),
+                    update_volume_labels.run_if(resource_changed::<AudioSettings>),
+                )
+                    .chain()
+                    .run_if(in_state(AppState::Settings)),
+            );
+    }
+}
+
+#[derive(Resource, Default)]
+struct Rebinding {
+    /// The action waiting for the player to press its new binding.
+    action: Option<InputAction>,
+    /// The action whose rebinding was aborted by a binding reserved for cancelling, shown until
+    /// the next press.
+    reserved: Option<InputAction>,
+}
+
+#[derive(Component)]
+struct SettingsUi;
+
+#[derive(Component)]
+#[require(Camera2d)]
+struct SettingsCamera;
+
+/// The text showing an action's bindings.
+#[derive(Component)]
+struct BindingLabel(InputAction);
+
+/// The text showing a channel's volume.
+#[derive(Component)]
+struct VolumeLabel(VolumeChannel);
+
+#[derive(Component)]
+#[require(Button)]
+enum SettingsButton {
+    /// Changes the channel's volume by the amount.
+    Volume(VolumeChannel, f32),
+    Rebind(InputAction),
+    ResetControls,
+    Back,
+}
+
+fn setup_settings(
+    mut commands: Commands,
+    game_assets: Res<GameAssets>,
+    bindings: Res<ControlBindings>,
+    audio_settings: Res<AudioSettings>,
+) {
+    commands.spawn(SettingsCamera);
+
+    commands
+        .spawn((
+            SettingsUi,
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(20.0),
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("Settings"),
+                TextFont {
+                    font: game_assets.audiowide_font.clone(),
+                    font_size: 60.0,
+                    ..default()
+                },
+            ));
+
+            parent
+                .spawn(Node {
+                    column_gap: Val::Px(60.0),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn(column_node()).with_children(|parent| {
+                        for channel in VolumeChannel::ALL {
+                            parent.spawn(volume_row(channel, &audio_settings, &game_assets));
+                        }
+                    });
+
+                    parent.spawn(column_node()).with_children(|parent| {
+                        for action in InputAction::ALL {
+                            parent.spawn((
+                                Node {
+                                    width: Val::Px(500.0),
+                                    justify_content: JustifyContent::SpaceBetween,
+                                    align_items: AlignItems::Center,
+                                    ..default()
+                                },
+                                children![
+                                    settings_text(action.name(), 20.0, &game_assets),
+                                    (
+                                        SettingsButton::Rebind(action),
+                                        button_node(200.0),
+                                        BorderColor(NORMAL_BUTTON),
+                                        BorderRadius::all(Val::Px(6.0)),
+                                        children![(
+                                            BindingLabel(action),
+                                            settings_text(
+                                                &bindings_display(&bindings, action),
+                                                20.0,
+                                                &game_assets
+                                            ),
+                                            TextColor(NORMAL_BUTTON),
+                                        )],
+                                    ),
+                                ],
+                            ));
+                        }
+                    });
+                });
+
+            parent.spawn(create_button(
+                SettingsButton::ResetControls,
+                "Reset controls",
+                &game_assets,
+            ));
+            parent.spawn(create_button(SettingsButton::Back, "Back", &game_assets));
+        });
+}
+
+fn cleanup_settings(
+    mut commands: Commands,
+    ui_id: Single<Entity, With<SettingsUi>>,
+    camera_id: Single<Entity, With<SettingsCamera>>,
+    mut rebinding: ResMut<Rebinding>,
+) {
+    commands.entity(*ui_id).despawn();
+    commands.entity(*camera_id).despawn();
+    *rebinding = Rebinding::default();
+}
+
+/// Binds the first button pressed to the action being rebound. Escape and the cancel action's
+/// bindings are reserved for aborting, unless the cancel action itself is being rebound.
+fn capture_binding(
+    mut evr_keyboard: EventReader<KeyboardInput>,
+    mut evr_mouse_button: EventReader<MouseButtonInput>,
+    mut evr_gamepad_button: EventReader<GamepadButtonStateChangedEvent>,
+    mut rebinding: ResMut<Rebinding>,
+    mut bindings: ResMut<ControlBindings>,
+) {
+    let keys = evr_keyboard
+        .read()
+        .filter(|event| event.state.is_pressed())
+        .map(|event| Binding::Key(event.key_code));
+    let mouse_buttons = evr_mouse_button
+        .read()
+        .filter(|event| event.state.is_pressed())
+        .map(|event| Binding::Mouse(event.button));
+    let gamepad_buttons = evr_gamepad_button
+        .read()
+        .filter(|event| event.state.is_pressed())
+        .map(|event| Binding::Gamepad(event.button));
+    // Always read them all so presses from before rebinding started, including the click on the
+    // rebind button, are never used
+    let pressed: Vec<Binding> = keys.chain(mouse_buttons).chain(gamepad_buttons).collect();
+    let Some(binding) = pressed.first() else {
+        return;
+    };
+
+    rebinding.reserved = None;
+    let Some(action) = rebinding.action.take() else {
+        return;
+    };
+
+    let reserved = *binding == Binding::Key(KeyCode::Escape)
+        || bindings.get(InputAction::Cancel).contains(binding);
+    if reserved && action != InputAction::Cancel {
+        rebinding.reserved = Some(action);
+    } else {
+        bindings.set(action, *binding);
+    }
+}
+
+fn button_interaction(
+    mut next_app_state: ResMut<NextState<AppState>>,
+    mut rebinding: ResMut<Rebinding>,
+    mut bindings: ResMut<ControlBindings>,
+    mut audio_settings: ResMut<AudioSettings>,
+    mut q_interaction: Query<
+        (
+            &Interaction,
+            &SettingsButton,
+            &mut Button,
+            &mut BorderColor,
+            &Children,
+        ),
+        Changed<Interaction>,
+    >,
+    mut q_text_color: Query<&mut TextColor>,
+) -> Result {
+    for (interaction, settings_button, mut button, mut border_color, children) in
+        q_interaction.iter_mut()
+    {
+        let mut text_color = q_text_color.get_mut(children[0])?;
+
+        match *interaction {
+            Interaction::Pressed => {
+                *text_color = TextColor(PRESSED_BUTTON);
+                *border_color = BorderColor(PRESSED_BUTTON);
+                button.set_changed();
+
+                match *settings_button {
+                    SettingsButton::Volume(channel, delta) => {
+                        audio_settings.adjust(channel, delta);
+                    }
+                    SettingsButton::Rebind(action) => {
+                        rebinding.action = Some(action);
+                    }
+                    SettingsButton::ResetControls => {
+                        *bindings = ControlBindings::default();
+                        *rebinding = Rebinding::default();
+                    }
+                    SettingsButton::Back => {
+                        next_app_state.set(AppState::Menu);
+                    }
+                }
+            }
+            Interaction::Hovered => {
+                *text_color = TextColor(HOVERED_BUTTON);
+                *border_color = BorderColor(HOVERED_BUTTON);
+                button.set_changed();
+            }
+            Interaction::None => {
+                *text_color = TextColor(NORMAL_BUTTON);
+                *border_color = BorderColor(NORMAL_BUTTON);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn update_binding_labels(
+    bindings: Res<ControlBindings>,
+    rebinding: Res<Rebinding>,
+    mut q_label: Query<(&BindingLabel, &mut Text)>,
+) {
+    for (label, mut text) in q_label.iter_mut() {
+        text.0 = if rebinding.action == Some(label.0) {
+            "Press a key...".to_string()
+        } else if rebinding.reserved == Some(label.0) {
+            "Reserved for cancel".to_string()
+        } else {
+            bindings_display(&bindings, label.0)
+        };
+    }
+}
+
+fn update_volume_labels(
+    audio_settings: Res<AudioSettings>,
+    mut q_label: Query<(&VolumeLabel, &mut Text)>,
+) {
+    for (label, mut text) in q_label.iter_mut() {
+        text.0 = volume_display(&audio_settings, label.0);
+    }
+}
+
+fn volume_display(audio_settings: &AudioSettings, channel: VolumeChannel) -> String {
+    format!("{:.0}%", audio_settings.get(channel) * 100.0)
+}
+
+fn bindings_display(bindings: &ControlBindings, action: InputAction) -> String {
+    let names: Vec<String> = bindings.get(action).iter().map(Binding::display).collect();
+    if names.is_empty() {
+        "Unbound".to_string()
+    } else {
+        names.join(" / ")
+    }
+}
+
+fn settings_text(text: &str, font_size: f32, game_assets: &GameAssets) -> impl Bundle + use<> {
+    (
+        Text::new(text),
+        TextFont {
+            font: game_assets.audiowide_font.clone(),
+            font_size,
+            ..default()
+        },
+    )
+}
+
+fn column_node() -> Node {
+    Node {
+        flex_direction: FlexDirection::Column,
+        row_gap: Val::Px(5.0),
+        ..default()
+    }
+}
+
+fn volume_row(
+    channel: VolumeChannel,
+    audio_settings: &AudioSettings,
+    game_assets: &GameAssets,
+) -> impl Bundle + use<> {
+    (
+        Node {
+            width: Val::Px(400.0),
+            justify_content: JustifyContent::SpaceBetween,
+            align_items: AlignItems::Center,
+            column_gap: Val::Px(10.0),
+            ..default()
+        },
+        children![
+            settings_text(channel.name(), 20.0, game_assets),
+            (
+                Node {
+                    align_items: AlignItems::Center,
+                    column_gap: Val::Px(10.0),
+                    ..default()
+                },
+                children![
+                    volume_button(channel, -VOLUME_STEP, "-", game_assets),
+                    (
+                        VolumeLabel(channel),
+                        Node {
+                            width: Val::Px(60.0),
+                            justify_content: JustifyContent::Center,
+                            ..default()
+                        },
+                        settings_text(&volume_display(audio_settings, channel), 20.0, game_assets),
+                    ),
+                    volume_button(channel, VOLUME_STEP, "+", game_assets),
+                ],
+            ),
+        ],
+    )
+}
+
+fn volume_button(
+    channel: VolumeChannel,
+    delta: f32,
+    text: &str,
+    game_assets: &GameAssets,
+) -> impl Bundle + use<> {
+    (
+        SettingsButton::Volume(channel, delta),
+        button_node(40.0),
+        BorderColor(NORMAL_BUTTON),
+        BorderRadius::all(Val::Px(6.0)),
+        children![(
+            settings_text(text, 20.0, game_assets),
+            TextColor(NORMAL_BUTTON)
+        )],
+    )
+}
+
+fn button_node(width: f32) -> Node {
+    Node {
+        width: Val::Px(width),
+        border: UiRect::all(Val::Px(2.0)),
+        padding: UiRect::all(Val::Px(4.0)),
+        justify_content: JustifyContent::Center,
+        ..default()
+    }
+}
+
+fn create_button(
+    button: SettingsButton,
+    text: &str,
+    game_assets: &GameAssets,
+) -> impl Bundle + use<> {
+    (
+        button,
+        button_node(300.0),
+        BorderColor(NORMAL_BUTTON),
+        BorderRadius::all(Val::Px(6.0)),
+        children![(
+            settings_text(text, 30.0, game_assets),
+            TextColor(NORMAL_BUTTON)
+        )],
+    )
+}
@@ -0,0 +1,360 @@
+use bevy::{audio::Volume, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    AppState, Team,
+    explosion::Explosion,
+    health::{DamageEvent, DiedEvent},
+    loading::LoadingAssets,
+    persistence::{load_ron, save_ron},
+    player::{GunFiredEvent, Player},
+    score::PlayerScore,
+    tower::TriggerTowerEvent,
+    waves::{WaveList, WaveManager},
+};
+
+const AUDIO_SETTINGS_PATH: &str = "audio.ron";
+/// How much each tower in a chain raises the pitch of its sounds.
+const CHAIN_PITCH_STEP: f32 = 0.06;
+/// Chains longer than this don't raise the pitch any further.
+const MAX_CHAIN_PITCH_STEPS: usize = 16;
+/// Chains at least this long layer a chime over tower triggers.
+const CHAIN_LAYER_LENGTH: usize = 3;
+/// The combo at which combo alone drives the music to full intensity.
+const MAX_INTENSITY_COMBO: f32 = 20.0;
+/// How far intensity moves towards its target each second, rising faster than it falls.
+const INTENSITY_RISE_SPEED: f32 = 0.5;
+const INTENSITY_FALL_SPEED: f32 = 0.2;
+/// The intensity range over which a music layer fades in.
+const LAYER_FADE_RANGE: f32 = 0.25;
+
+/// Sound effects for gameplay events, and music that gets more intense with the player's combo
+/// and the wave stage.
+pub struct SoundPlugin;
+
+impl Plugin for SoundPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(load_audio_settings())
+            .init_resource::<MusicIntensity>()
+            .add_systems(Startup, load_sounds)
+            .add_systems(OnExit(AppState::Loading), start_music)
+            .add_systems(OnExit(AppState::Settings), save_audio_settings)
+            .add_systems(
+                Update,
+                (
+                    play_gun_sounds.run_if(on_event::<GunFiredEvent>),
+                    play_tower_sounds.run_if(on_event::<TriggerTowerEvent>),
+                    play_explosion_sounds,
+                    play_death_sounds.run_if(on_event::<DiedEvent>),
+                    play_damage_sounds.run_if(on_event::<DamageEvent>),
+                )
+                    .run_if(in_state(AppState::InGame)),
+            )
+            .add_systems(Update, update_music);
+    }
+}
+
+#[derive(Resource)]
+struct Sounds {
+    fire: Handle<AudioSource>,
+    tower_trigger: Handle<AudioSource>,
+    chain: Handle<AudioSource>,
+    explosion: Handle<AudioSource>,
+    enemy_death: Handle<AudioSource>,
+    player_hit: Handle<AudioSource>,
+    /// Music layers that play in sync, each one fades in at a higher intensity.
+    music_layers: Vec<(Handle<AudioSource>, f32)>,
+}
+
+/// The volume of each channel from 0 to 1, music and sound effects are scaled by master.
+#[derive(Resource, Serialize, Deserialize, Debug, Clone)]
+pub struct AudioSettings {
+    pub master: f32,
+    pub music: f32,
+    pub sfx: f32,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self {
+            master: 0.8,
+            music: 0.6,
+            sfx: 0.8,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VolumeChannel {
+    Master,
+    Music,
+    Sfx,
+}
+
+impl VolumeChannel {
+    pub const ALL: [VolumeChannel; 3] = [
+        VolumeChannel::Master,
+        VolumeChannel::Music,
+        VolumeChannel::Sfx,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            VolumeChannel::Master => "Master volume",
+            VolumeChannel::Music => "Music volume",
+            VolumeChannel::Sfx => "Effects volume",
+        }
+    }
+}
+
+impl AudioSettings {
+    pub fn get(&self, channel: VolumeChannel) -> f32 {
+        match channel {
+            VolumeChannel::Master => self.master,
+            VolumeChannel::Music => self.music,
+            VolumeChannel::Sfx => self.sfx,
+        }
+    }
+
+    /// Changes a channel's volume, keeping it between 0 and 1 in steps of 10%.
+    pub fn adjust(&mut self, channel: VolumeChannel, delta: f32) {
+        let volume = match channel {
+            VolumeChannel::Master => &mut self.master,
+            VolumeChannel::Music => &mut self.music,
+            VolumeChannel::Sfx => &mut self.sfx,
+        };
+        *volume = ((*volume + delta).clamp(0.0, 1.0) * 10.0).round() / 10.0;
+    }
+
+    fn music_volume(&self) -> f32 {
+        self.master * self.music
+    }
+
+    fn sfx_volume(&self) -> f32 {
+        self.master * self.sfx
+    }
+}
+
+/// How intense the music currently is from 0 (calm) to 1.
+#[derive(Resource, Default)]
+struct MusicIntensity(f32);
+
+#[derive(Component)]
+struct MusicLayer {
+    /// The intensity at which this layer starts fading in.
+    start: f32,
+}
+
+fn load_sounds(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut loading_assets: ResMut<LoadingAssets>,
+) {
+    let sounds = Sounds {
+        fire: asset_server.load("audio/fire.wav"),
+        tower_trigger: asset_server.load("audio/tower_trigger.wav"),
+        chain: asset_server.load("audio/chain.wav"),
+        explosion: asset_server.load("audio/explosion.wav"),
+        enemy_death: asset_server.load("audio/enemy_death.wav"),
+        player_hit: asset_server.load("audio/player_hit.wav"),
+        music_layers: vec![
+            (asset_server.load("audio/music_base.wav"), 0.0),
+            (asset_server.load("audio/music_drums.wav"), 0.3),
+            (asset_server.load("audio/music_lead.wav"), 0.65),
+        ],
+    };
+
+    // Music layers must be loaded before they start so they stay in sync
+    loading_assets.extend(
+        sounds
+            .music_layers
+            .iter()
+            .map(|(handle, _)| handle.clone().untyped()),
+    );
+
+    commands.insert_resource(sounds);
+}
+
+fn start_music(mut commands: Commands, sounds: Res<Sounds>) {
+    for (handle, start) in sounds.music_layers.iter() {
+        commands.spawn((
+            MusicLayer { start: *start },
+            AudioPlayer::new(handle.clone()),
+            PlaybackSettings::LOOP.with_volume(Volume::Linear(0.0)),
+        ));
+    }
+}
+
+/// Playback speed for a sound caused by a chain of tower triggers, so long chains audibly
+/// escalate.
+fn chain_speed(chain_length: usize) -> f32 {
+    1.0 + chain_length.min(MAX_CHAIN_PITCH_STEPS) as f32 * CHAIN_PITCH_STEP
+}
+
+fn play_sfx(commands: &mut Commands, sound: &Handle<AudioSource>, volume: f32, speed: f32) {
+    commands.spawn((
+        AudioPlayer::new(sound.clone()),
+        PlaybackSettings::DESPAWN
+            .with_volume(Volume::Linear(volume))
+            .with_speed(speed),
+    ));
+}
+
+// Each system plays at most one sound a frame (for the longest chain) so big chain reactions
+// don't stack dozens of identical sounds.
+
+fn play_gun_sounds(
+    mut commands: Commands,
+    sounds: Res<Sounds>,
+    settings: Res<AudioSettings>,
+    mut evr_gun_fired: EventReader<GunFiredEvent>,
+) {
+    evr_gun_fired.clear();
+    play_sfx(
+        &mut commands,
+        &sounds.fire,
+        settings.sfx_volume() * 0.5,
+        1.0,
+    );
+}
+
+fn play_tower_sounds(
+    mut commands: Commands,
+    sounds: Res<Sounds>,
+    settings: Res<AudioSettings>,
+    mut evr_trigger_tower: EventReader<TriggerTowerEvent>,
+) {
+    let Some(chain_length) = evr_trigger_tower
+        .read()
+        .map(|event| event.trigger_history.len())
+        .max()
+    else {
+        return;
+    };
+
+    let volume = settings.sfx_volume();
+    play_sfx(
+        &mut commands,
+        &sounds.tower_trigger,
+        volume,
+        chain_speed(chain_length),
+    );
+
+    if chain_length >= CHAIN_LAYER_LENGTH {
+        play_sfx(
+            &mut commands,
+            &sounds.chain,
+            volume * 0.6,
+            chain_speed(chain_length - CHAIN_LAYER_LENGTH),
+        );
+    }
+}
+
+fn play_explosion_sounds(
+    mut commands: Commands,
+    sounds: Res<Sounds>,
+    settings: Res<AudioSettings>,
+    q_explosions: Query<&Explosion, Added<Explosion>>,
+) {
+    // Enemies exploding on death already play their own sound
+    let Some(chain_length) = q_explosions
+        .iter()
+        .filter(|explosion| explosion.team == Team::Player)
+        .map(|explosion| explosion.trigger_history.len())
+        .max()
+    else {
+        return;
+    };
+
+    play_sfx(
+        &mut commands,
+        &sounds.explosion,
+        settings.sfx_volume(),
+        chain_speed(chain_length),
+    );
+}
+
+fn play_death_sounds(
+    mut commands: Commands,
+    sounds: Res<Sounds>,
+    settings: Res<AudioSettings>,
+    mut evr_died: EventReader<DiedEvent>,
+    player: Option<Single<Entity, With<Player>>>,
+) {
+    let player = player.map(|player| *player);
+    // The player dying is covered by the damage sound
+    let Some(chain_length) = evr_died
+        .read()
+        .filter(|event| Some(event.entity) != player)
+        .map(|event| event.chain_length)
+        .max()
+    else {
+        return;
+    };
+
+    play_sfx(
+        &mut commands,
+        &sounds.enemy_death,
+        settings.sfx_volume() * 0.7,
+        chain_speed(chain_length),
+    );
+}
+
+fn play_damage_sounds(
+    mut commands: Commands,
+    sounds: Res<Sounds>,
+    settings: Res<AudioSettings>,
+    mut evr_damage: EventReader<DamageEvent>,
+    player: Single<Entity, With<Player>>,
+) {
+    if evr_damage.read().any(|event| event.target == *player) {
+        play_sfx(
+            &mut commands,
+            &sounds.player_hit,
+            settings.sfx_volume(),
+            1.0,
+        );
+    }
+}
+
+fn update_music(
+    time: Res<Time>,
+    app_state: Res<State<AppState>>,
+    score: Res<PlayerScore>,
+    wave_manager: Res<WaveManager>,
+    wave_lists: Res<Assets<WaveList>>,
+    settings: Res<AudioSettings>,
+    mut intensity: ResMut<MusicIntensity>,
+    mut q_layers: Query<(&MusicLayer, &mut AudioSink)>,
+) {
+    let target = if *app_state.get() == AppState::InGame {
+        let combo = (score.combo as f32 / MAX_INTENSITY_COMBO).min(1.0);
+        let stage = wave_manager.current_wave(&wave_lists).map_or(0.0, |wave| {
+            wave_manager.stage_display() as f32 / wave.stages.len() as f32
+        });
+        (combo * 0.6 + stage * 0.4).min(1.0)
+    } else {
+        0.0
+    };
+
+    let delta = time.delta_secs();
+    intensity.0 +=
+        (target - intensity.0).clamp(-INTENSITY_FALL_SPEED * delta, INTENSITY_RISE_SPEED * delta);
+
+    for (layer, mut sink) in q_layers.iter_mut() {
+        let layer_volume = if layer.start <= 0.0 {
+            1.0
+        } else {
+            ((intensity.0 - layer.start) / LAYER_FADE_RANGE).clamp(0.0, 1.0)
+        };
+        sink.set_volume(Volume::Linear(layer_volume * settings.music_volume()));
+    }
+}
+
+fn load_audio_settings() -> AudioSettings {
+    load_ron(AUDIO_SETTINGS_PATH, "audio settings")
+}
+
+fn save_audio_settings(settings: Res<AudioSettings>) {
+    save_ron(AUDIO_SETTINGS_PATH, "audio settings", &*settings);
+}
@@ -0,0 +1,155 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use hexx::{EdgeDirection, Hex};
+
+use crate::{
+    EnemyTeam,
+    arena::Arena,
+    arena_index::ArenaIndex,
+    game_assets::GameAssets,
+    health::{DamageEvent, Health},
+    materials::BeamMaterial,
+    tower::TriggerTowerEvent,
+};
+
+const BEAM_DURATION: Duration = Duration::from_millis(300);
+
+/// The visual left behind by a beam, the beam itself hits everything instantly.
+#[derive(Component)]
+pub struct Beam {
+    /// The time until this entity is removed.
+    timer: Timer,
+}
+
+/// Instantly damages every enemy and triggers every tower in a line from `origin` to the edge of
+/// the arena.
+pub struct FireBeamCommand {
+    /// The hex the beam is fired from, it isn't hit by the beam.
+    pub origin: Hex,
+    pub direction: EdgeDirection,
+    pub damage: u16,
+    /// A list of all towers that have been triggered in this event chain, including the tower
+    /// firing the beam.
+    pub trigger_history: Vec<Entity>,
+}
+
+impl Command for FireBeamCommand {
+    fn apply(self, world: &mut World) -> () {
+        let hexes: Vec<Hex> = Arena::line_to_edge(self.origin, self.direction).collect();
+
+        let towers: Vec<Entity> = {
+            let arena_index = world.resource::<ArenaIndex>();
+            hexes
+                .iter()
+                .filter_map(|hex| arena_index.tower_index.get(hex).copied())
+                .collect()
+        };
+
+        damage_enemies_in_hexes(world, &hexes, self.damage, self.trigger_history.len());
+
+        for target in towers {
+            world.send_event(TriggerTowerEvent {
+                target,
+                trigger_history: self.trigger_history.clone(),
+            });
+        }
+
+        let Some(last_hex) = hexes.last() else {
+            // Fired from the edge of the arena, there's nothing to show
+            return;
+        };
+        let (start, end) = {
+            let layout = &world.resource::<Arena>().layout;
+            (
+                layout.hex_to_world_pos(self.origin),
+                layout.hex_to_world_pos(*last_hex),
+            )
+        };
+        spawn_beam_visual(world, start, end, LinearRgba::new(0.2, 1.0, 0.2, 1.0));
+    }
+}
+
+/// Damages every enemy in `hexes`.
+pub(super) fn damage_enemies_in_hexes(
+    world: &mut World,
+    hexes: &[Hex],
+    damage: u16,
+    chain_length: usize,
+) {
+    let targets = world
+        .resource::<ArenaIndex>()
+        .get_many_index(hexes.iter().copied());
+
+    let mut q_enemies = world.query_filtered::<(), (With<EnemyTeam>, With<Health>)>();
+    let enemies: Vec<Entity> = targets
+        .into_iter()
+        .filter(|id| q_enemies.get(world, *id).is_ok())
+        .collect();
+
+    for target in enemies {
+        world.send_event(DamageEvent {
+            target,
+            damage,
+            chain_length,
+        });
+    }
+}
+
+/// Spawns a `Beam` visual from `start` to `end`, does nothing when running headless.
+pub(super) fn spawn_beam_visual(world: &mut World, start: Vec2, end: Vec2, color: LinearRgba) {
+    let Some(beam_mesh) = world
+        .get_resource::<GameAssets>()
+        .map(|game_assets| game_assets.beam_mesh.clone())
+    else {
+        return;
+    };
+
+    let material = world
+        .resource_mut::<Assets<BeamMaterial>>()
+        .add(BeamMaterial {
+            color,
+            duration: BEAM_DURATION.as_secs_f32(),
+            ..default()
+        });
+
+    // The mesh is one unit long, so it's stretched along its length to reach the end
+    let center = (start + end) / 2.0;
+    let transform = Transform::from_xyz(center.x, 0.5, center.y)
+        .looking_at(Vec3::new(end.x, 0.5, end.y), Vec3::Y)
+        .with_scale(Vec3::new(1.0, 1.0, start.distance(end)));
+
+    world.spawn((
+        Beam {
+            timer: Timer::new(BEAM_DURATION, TimerMode::Once),
+        },
+        Mesh3d(beam_mesh),
+        MeshMaterial3d(material),
+        transform,
+    ));
+}
+
+pub(super) fn update_beams(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut q_beams: Query<(Entity, &mut Beam, &MeshMaterial3d<BeamMaterial>)>,
+    mut materials: ResMut<Assets<BeamMaterial>>,
+) {
+    for (entity, mut beam, material) in q_beams.iter_mut() {
+        beam.timer.tick(time.delta());
+        if beam.timer.finished() {
+            commands.entity(entity).try_despawn();
+            continue;
+        }
+
+        if let Some(material) = materials.get_mut(material) {
+            material.time = beam.timer.elapsed_secs();
+        }
+    }
+}
+
+pub(super) fn cleanup_beams(mut commands: Commands, q_beams: Query<Entity, With<Beam>>) {
+    for entity in q_beams {
+        commands.entity(entity).despawn();
+    }
+}
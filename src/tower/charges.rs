@@ -0,0 +1,95 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::tower::TowerDefinition;
+
+/// How many more times a tower can be triggered, it gets a charge back every cooldown until it's
+/// full.
+#[derive(Component, Debug)]
+pub struct TowerCharges {
+    charges: u32,
+    max_charges: u32,
+    cooldown: Timer,
+}
+
+impl TowerCharges {
+    pub fn new(max_charges: u32, cooldown: Duration) -> Self {
+        Self {
+            charges: max_charges,
+            max_charges,
+            cooldown: Timer::new(cooldown, TimerMode::Repeating),
+        }
+    }
+
+    /// Fully charged charges for a tower built from `definition`.
+    pub fn from_definition(definition: &TowerDefinition) -> Self {
+        Self::new(
+            definition.charges,
+            Duration::from_secs_f32(definition.cooldown),
+        )
+    }
+
+    pub fn charges(&self) -> u32 {
+        self.charges
+    }
+
+    /// Sets how many charges are left, at most `max_charges`.
+    pub fn set_charges(&mut self, charges: u32) {
+        self.charges = charges.min(self.max_charges);
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.charges >= self.max_charges
+    }
+
+    /// How charged the tower is, counting the charge being recharged. 1.0 when full.
+    pub fn fraction(&self) -> f32 {
+        if self.is_full() {
+            return 1.0;
+        }
+        (self.charges as f32 + self.cooldown.fraction()) / self.max_charges as f32
+    }
+
+    /// Uses up a charge, returns `false` if there are none left.
+    pub(super) fn try_use(&mut self) -> bool {
+        if self.charges == 0 {
+            return false;
+        }
+        self.charges -= 1;
+        true
+    }
+}
+
+pub(super) fn recharge_towers(time: Res<Time>, mut q_charges: Query<&mut TowerCharges>) {
+    for mut charges in q_charges.iter_mut() {
+        if charges.is_full() {
+            continue;
+        }
+
+        charges.cooldown.tick(time.delta());
+        let recharged = charges.cooldown.times_finished_this_tick();
+        charges.charges = (charges.charges + recharged).min(charges.max_charges);
+        if charges.is_full() {
+            // The next cooldown starts from scratch when a charge is used
+            charges.cooldown.reset();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn charges_recharge_one_per_cooldown() {
+        let mut charges = TowerCharges::new(2, Duration::from_secs(1));
+        assert!(charges.try_use());
+        assert!(charges.try_use());
+        assert!(!charges.try_use());
+        assert_eq!(charges.fraction(), 0.0);
+
+        charges.cooldown.tick(Duration::from_millis(500));
+        assert_eq!(charges.fraction(), 0.25);
+    }
+}
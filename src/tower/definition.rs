@@ -0,0 +1,386 @@
+use std::{fmt, time::Duration};
+
+use bevy::{platform::collections::HashSet, prelude::*};
+use hexx::EdgeDirection;
+use serde::Deserialize;
+
+use crate::ron_list::{RonList, RonListLoader};
+
+/// The highest level a tower can be upgraded to by merging identical towers.
+pub const MAX_TOWER_LEVEL: u8 = 3;
+/// The longest fuse, gravity well or cooldown, longer times are almost certainly typos.
+const MAX_ACTION_SECONDS: f32 = 60.0;
+
+/// Identifies a tower definition, e.g. `"bullet2"`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize)]
+#[serde(transparent)]
+pub struct TowerId(pub String);
+
+impl From<&str> for TowerId {
+    fn from(id: &str) -> Self {
+        TowerId(id.to_string())
+    }
+}
+
+impl fmt::Display for TowerId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// All tower definitions, loaded from a `.towers.ron` file by `TowerListLoader`.
+#[derive(Asset, TypePath, Deserialize, Debug)]
+pub struct TowerList {
+    pub towers: Vec<TowerDefinition>,
+}
+
+impl TowerList {
+    pub fn get(&self, id: &TowerId) -> Option<&TowerDefinition> {
+        self.towers.iter().find(|tower| tower.id == *id)
+    }
+}
+
+impl RonList for TowerList {
+    const NAME: &'static str = "towers";
+    const EXTENSIONS: &'static [&'static str] = &["towers.ron"];
+
+    /// Checks that every tower can be used, the error names the offending tower.
+    fn validate(&self) -> Result<(), String> {
+        let mut ids = HashSet::new();
+        for tower in self.towers.iter() {
+            if tower.id.0.is_empty() {
+                return Err(format!("tower `{}` has an empty id", tower.name));
+            }
+            if !ids.insert(&tower.id) {
+                return Err(format!("tower `{}` is defined more than once", tower.id));
+            }
+            if tower
+                .actions
+                .iter()
+                .all(|action| matches!(action, TowerAction::Fuse { .. }))
+            {
+                return Err(format!("tower `{}` has no actions", tower.id));
+            }
+            if tower.health == 0 {
+                return Err(format!("tower `{}` must have at least 1 health", tower.id));
+            }
+            if tower.charges == 0 {
+                return Err(format!("tower `{}` must have at least 1 charge", tower.id));
+            }
+            if !is_valid_action_time(tower.cooldown) {
+                return Err(format!(
+                    "tower `{}` cooldown must be more than 0 and at most 60 seconds",
+                    tower.id
+                ));
+            }
+            if tower.reflect_axis().is_some() && tower.actions.len() > 1 {
+                return Err(format!(
+                    "tower `{}` reflects bullets so it can't have other actions",
+                    tower.id
+                ));
+            }
+            for (action_index, action) in tower.actions.iter().enumerate() {
+                let error = match action {
+                    TowerAction::Explode { range: 0, .. } => "explosion range must be at least 1",
+                    TowerAction::Lightning { range: 0, .. } => "lightning range must be at least 1",
+                    TowerAction::Lightning { arcs: 0, .. } => "lightning must have at least 1 arc",
+                    TowerAction::Fuse { seconds } if !is_valid_action_time(*seconds) => {
+                        "fuse time must be more than 0 and at most 60 seconds"
+                    }
+                    TowerAction::GravityWell { range: 0, .. } => {
+                        "gravity well range must be at least 1"
+                    }
+                    TowerAction::GravityWell { seconds, .. } if !is_valid_action_time(*seconds) => {
+                        "gravity well time must be more than 0 and at most 60 seconds"
+                    }
+                    _ => continue,
+                };
+                return Err(format!(
+                    "tower `{}`, action {}: {error}",
+                    tower.id,
+                    action_index + 1
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Whether `seconds` can be used as an action's duration or a cooldown. Huge values are rejected
+/// because `Duration::from_secs_f32` panics on them.
+fn is_valid_action_time(seconds: f32) -> bool {
+    seconds.is_finite() && seconds > 0.0 && seconds <= MAX_ACTION_SECONDS
+}
+
+#[derive(Deserialize, Debug)]
+pub struct TowerDefinition {
+    pub id: TowerId,
+    /// The name shown to the player.
+    pub name: String,
+    /// Path to the icon shown in the hotbar and reward selection.
+    pub icon: String,
+    /// Path to the texture shown on top of the tower and its placeholder.
+    pub texture: String,
+    /// What the tower does when triggered.
+    pub actions: Vec<TowerAction>,
+    /// How many times the tower can be triggered before it has to recharge, a tower with more
+    /// than 1 charge can be triggered again within the same chain.
+    #[serde(default = "default_charges")]
+    pub charges: u32,
+    /// Seconds it takes to get back one charge.
+    #[serde(default = "default_cooldown")]
+    pub cooldown: f32,
+    /// How much damage from enemies the tower takes before it's destroyed.
+    #[serde(default = "default_health")]
+    pub health: u16,
+}
+
+impl TowerDefinition {
+    /// How long the tower waits after being triggered before running its other actions, `None`
+    /// if it runs them straight away.
+    pub fn fuse(&self) -> Option<Duration> {
+        self.actions.iter().find_map(|action| match *action {
+            TowerAction::Fuse { seconds } => Some(Duration::from_secs_f32(seconds)),
+            _ => None,
+        })
+    }
+
+    /// Whether the tower `entity` can be triggered again in a chain, each charge lets a tower
+    /// appear in the chain's trigger history once.
+    pub fn can_trigger_in_chain(&self, entity: Entity, trigger_history: &[Entity]) -> bool {
+        let times_triggered = trigger_history.iter().filter(|id| **id == entity).count();
+        times_triggered < self.charges as usize
+    }
+
+    /// Whether triggering the tower does anything, mirrors only reflect bullets.
+    pub fn has_triggered_action(&self) -> bool {
+        self.actions
+            .iter()
+            .any(|action| !matches!(action, TowerAction::Reflect { .. }))
+    }
+
+    /// The mirror axis of a tower that reflects bullets, `None` if bullets trigger it instead.
+    pub fn reflect_axis(&self) -> Option<TowerDirection> {
+        self.actions.iter().find_map(|action| match *action {
+            TowerAction::Reflect { axis } => Some(axis),
+            _ => None,
+        })
+    }
+
+    /// The tower's actions when upgraded to `level` (starting from 1). Each level adds 1 damage,
+    /// 1 explosion and gravity well range and 1 lightning arc, and max level towers also shoot
+    /// clockwise of each direction.
+    pub fn actions_at_level(&self, level: u8) -> Vec<TowerAction> {
+        let bonus = level.saturating_sub(1);
+        let mut actions: Vec<TowerAction> = self
+            .actions
+            .iter()
+            .map(|action| match *action {
+                TowerAction::Shoot { direction, damage } => TowerAction::Shoot {
+                    direction,
+                    damage: damage + bonus as u16,
+                },
+                TowerAction::Explode { range, damage } => TowerAction::Explode {
+                    range: range + bonus as u32,
+                    damage: damage + bonus as u16,
+                },
+                TowerAction::Beam { direction, damage } => TowerAction::Beam {
+                    direction,
+                    damage: damage + bonus as u16,
+                },
+                TowerAction::Lightning {
+                    range,
+                    arcs,
+                    damage,
+                } => TowerAction::Lightning {
+                    range,
+                    arcs: arcs + bonus as u32,
+                    damage: damage + bonus as u16,
+                },
+                TowerAction::Fuse { seconds } => TowerAction::Fuse { seconds },
+                TowerAction::GravityWell { range, seconds } => TowerAction::GravityWell {
+                    range: range + bonus as u32,
+                    seconds,
+                },
+                TowerAction::Reflect { axis } => TowerAction::Reflect { axis },
+            })
+            .collect();
+
+        if level >= MAX_TOWER_LEVEL {
+            let directions: Vec<TowerDirection> = actions
+                .iter()
+                .filter_map(|action| match *action {
+                    TowerAction::Shoot { direction, .. } => Some(direction),
+                    TowerAction::Explode { .. }
+                    | TowerAction::Beam { .. }
+                    | TowerAction::Lightning { .. }
+                    | TowerAction::Fuse { .. }
+                    | TowerAction::GravityWell { .. }
+                    | TowerAction::Reflect { .. } => None,
+                })
+                .collect();
+            // Directions the tower already shoots in are skipped
+            let extra_shots: Vec<TowerAction> = actions
+                .iter()
+                .filter_map(|action| match *action {
+                    TowerAction::Shoot { direction, damage }
+                        if !directions.contains(&direction.clockwise()) =>
+                    {
+                        Some(TowerAction::Shoot {
+                            direction: direction.clockwise(),
+                            damage,
+                        })
+                    }
+                    _ => None,
+                })
+                .collect();
+            actions.extend(extra_shots);
+        }
+
+        actions
+    }
+}
+
+/// How much longer bullets shot by a tower at `level` last, each level adds half the base
+/// lifetime.
+pub fn bullet_lifetime_multiplier(level: u8) -> f32 {
+    1.0 + level.saturating_sub(1) as f32 * 0.5
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub enum TowerAction {
+    /// Shoots a bullet in a direction, the direction is rotated with the tower.
+    Shoot {
+        direction: TowerDirection,
+        #[serde(default = "default_damage")]
+        damage: u16,
+    },
+    /// Explodes damaging every hex within `range` of the tower.
+    Explode {
+        range: u32,
+        #[serde(default = "default_damage")]
+        damage: u16,
+    },
+    /// Instantly damages every enemy and triggers every tower in a line to the edge of the arena,
+    /// the direction is rotated with the tower.
+    Beam {
+        direction: TowerDirection,
+        #[serde(default = "default_damage")]
+        damage: u16,
+    },
+    /// Arcs to the `arcs` nearest towers within `range` that haven't been triggered yet,
+    /// damaging every enemy along the way.
+    Lightning {
+        range: u32,
+        arcs: u32,
+        #[serde(default = "default_damage")]
+        damage: u16,
+    },
+    /// Delays the tower's other actions by `seconds` after it's triggered, the tower ignores
+    /// triggers while waiting.
+    Fuse { seconds: f32 },
+    /// Pulls enemies within `range` towards the tower for `seconds`.
+    GravityWell { range: u32, seconds: f32 },
+    /// Bounces bullets off a mirror through the tower instead of being triggered by them, the
+    /// mirror lies along `axis` and its opposite and is rotated with the tower.
+    Reflect { axis: TowerDirection },
+}
+
+fn default_damage() -> u16 {
+    1
+}
+
+fn default_charges() -> u32 {
+    1
+}
+
+fn default_cooldown() -> f32 {
+    0.5
+}
+
+fn default_health() -> u16 {
+    3
+}
+
+/// The edge directions of a flat topped hex, used instead of `EdgeDirection` in tower files.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TowerDirection {
+    Top,
+    TopRight,
+    BottomRight,
+    Bottom,
+    BottomLeft,
+    TopLeft,
+}
+
+impl TowerDirection {
+    /// The next direction clockwise.
+    pub fn clockwise(&self) -> TowerDirection {
+        match self {
+            TowerDirection::Top => TowerDirection::TopRight,
+            TowerDirection::TopRight => TowerDirection::BottomRight,
+            TowerDirection::BottomRight => TowerDirection::Bottom,
+            TowerDirection::Bottom => TowerDirection::BottomLeft,
+            TowerDirection::BottomLeft => TowerDirection::TopLeft,
+            TowerDirection::TopLeft => TowerDirection::Top,
+        }
+    }
+}
+
+impl From<TowerDirection> for EdgeDirection {
+    fn from(direction: TowerDirection) -> Self {
+        match direction {
+            TowerDirection::Top => EdgeDirection::FLAT_TOP,
+            TowerDirection::TopRight => EdgeDirection::FLAT_TOP_RIGHT,
+            TowerDirection::BottomRight => EdgeDirection::FLAT_BOTTOM_RIGHT,
+            TowerDirection::Bottom => EdgeDirection::FLAT_BOTTOM,
+            TowerDirection::BottomLeft => EdgeDirection::FLAT_BOTTOM_LEFT,
+            TowerDirection::TopLeft => EdgeDirection::FLAT_TOP_LEFT,
+        }
+    }
+}
+
+/// Keeps the handle to the tower definitions, look towers up with `TowerRegistry::get`.
+#[derive(Resource, Default)]
+pub struct TowerRegistry {
+    pub list: Handle<TowerList>,
+}
+
+impl TowerRegistry {
+    /// The definition of a tower, `None` if the towers aren't loaded or there's no such tower.
+    pub fn get<'a>(
+        &self,
+        tower_lists: &'a Assets<TowerList>,
+        id: &TowerId,
+    ) -> Option<&'a TowerDefinition> {
+        tower_lists.get(&self.list)?.get(id)
+    }
+}
+
+pub type TowerListLoader = RonListLoader<TowerList>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn action_times_must_fit_in_a_duration() {
+        let towers = |seconds: &str| -> TowerList {
+            ron::from_str(&format!(
+                r#"(towers: [(
+                    id: "fuse",
+                    name: "Time Bomb",
+                    icon: "icons/fuse.png",
+                    texture: "textures/fuse.png",
+                    actions: [Fuse(seconds: {seconds}), Explode(range: 2, damage: 2)],
+                )])"#
+            ))
+            .unwrap()
+        };
+
+        assert_eq!(towers("2.0").validate(), Ok(()));
+        assert!(towers("1e20").validate().is_err());
+        assert!(towers("0.0").validate().is_err());
+    }
+}
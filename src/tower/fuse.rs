@@ -0,0 +1,62 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::{
+    arena::Arena,
+    tower::{Tower, TowerList, TowerRegistry, run_tower_actions},
+};
+
+/// Added to a tower with a `TowerAction::Fuse` when it's triggered, its other actions run once
+/// the timer finishes.
+#[derive(Component)]
+pub struct ArmedFuse {
+    timer: Timer,
+    /// The trigger history when the tower was triggered, including the tower.
+    trigger_history: Vec<Entity>,
+}
+
+impl ArmedFuse {
+    pub fn new(fuse: Duration, trigger_history: Vec<Entity>) -> Self {
+        Self {
+            timer: Timer::new(fuse, TimerMode::Once),
+            trigger_history,
+        }
+    }
+
+    /// How much of the fuse is left, from 1.0 when armed to 0.0 when the payload fires.
+    pub fn fraction_remaining(&self) -> f32 {
+        self.timer.fraction_remaining()
+    }
+}
+
+pub(super) fn update_fuses(
+    mut commands: Commands,
+    time: Res<Time>,
+    arena: Res<Arena>,
+    tower_registry: Res<TowerRegistry>,
+    tower_lists: Res<Assets<TowerList>>,
+    mut q_armed: Query<(Entity, &Tower, &Transform, &mut ArmedFuse)>,
+) {
+    for (entity, tower, transform, mut fuse) in q_armed.iter_mut() {
+        fuse.timer.tick(time.delta());
+        if !fuse.timer.finished() {
+            continue;
+        }
+
+        commands.entity(entity).remove::<ArmedFuse>();
+
+        let Some(definition) = tower_registry.get(&tower_lists, &tower.id) else {
+            warn!(id=%tower.id, "Armed tower has no definition");
+            continue;
+        };
+        run_tower_actions(
+            &mut commands,
+            &arena,
+            tower,
+            transform,
+            definition,
+            &fuse.trigger_history,
+        );
+    }
+}
@@ -0,0 +1,73 @@
+use bevy::prelude::*;
+use hexx::Hex;
+
+use crate::{
+    arena::Arena,
+    arena_index::ArenaIndex,
+    tower::{
+        Tower, TowerList, TowerRegistry, TriggerTowerEvent,
+        beam::{damage_enemies_in_hexes, spawn_beam_visual},
+    },
+};
+
+/// Arcs from `origin` to the nearest other towers within `range` that can still be triggered in
+/// this chain (see `TowerDefinition::can_trigger_in_chain`), damaging every enemy along each arc
+/// and triggering the tower at its end. Mirrors are never arced to, triggering them does nothing.
+pub struct ArcLightningCommand {
+    /// The hex of the tower the lightning arcs from.
+    pub origin: Hex,
+    pub range: u32,
+    /// The most towers to arc to.
+    pub arcs: u32,
+    pub damage: u16,
+    /// A list of all towers that have been triggered in this event chain, including the tower
+    /// the lightning arcs from.
+    pub trigger_history: Vec<Entity>,
+}
+
+impl Command for ArcLightningCommand {
+    fn apply(self, world: &mut World) -> () {
+        let tower_registry = world.resource::<TowerRegistry>();
+        let tower_lists = world.resource::<Assets<TowerList>>();
+        let mut targets: Vec<(Hex, Entity)> = world
+            .resource::<ArenaIndex>()
+            .tower_index
+            .iter()
+            .filter(|(hex, id)| {
+                **hex != self.origin
+                    && hex.unsigned_distance_to(self.origin) <= self.range
+                    && world
+                        .get::<Tower>(**id)
+                        .and_then(|tower| tower_registry.get(tower_lists, &tower.id))
+                        .is_some_and(|definition| {
+                            definition.has_triggered_action()
+                                && definition.can_trigger_in_chain(**id, &self.trigger_history)
+                        })
+            })
+            .map(|(hex, id)| (*hex, *id))
+            .collect();
+        // Ties are broken by position so the same towers are picked every run
+        targets.sort_by_key(|(hex, _)| (hex.unsigned_distance_to(self.origin), hex.x, hex.y));
+        targets.truncate(self.arcs as usize);
+
+        for (hex, target) in targets {
+            // The origin hex is skipped, the tower being arced from is in it
+            let arc: Vec<Hex> = self.origin.line_to(hex).skip(1).collect();
+            damage_enemies_in_hexes(world, &arc, self.damage, self.trigger_history.len());
+
+            world.send_event(TriggerTowerEvent {
+                target,
+                trigger_history: self.trigger_history.clone(),
+            });
+
+            let (start, end) = {
+                let layout = &world.resource::<Arena>().layout;
+                (
+                    layout.hex_to_world_pos(self.origin),
+                    layout.hex_to_world_pos(hex),
+                )
+            };
+            spawn_beam_visual(world, start, end, LinearRgba::new(0.5, 0.7, 1.0, 1.0));
+        }
+    }
+}
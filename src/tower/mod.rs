@@ -0,0 +1,455 @@
+use std::{f32::consts::PI, time::Duration};
+
+use bevy::{pbr::ExtendedMaterial, prelude::*};
+use hexx::{EdgeDirection, Hex};
+
+mod beam;
+mod charges;
+mod definition;
+mod fuse;
+mod lightning;
+
+pub use beam::{Beam, FireBeamCommand};
+pub use charges::TowerCharges;
+pub use definition::{
+    MAX_TOWER_LEVEL, TowerAction, TowerDefinition, TowerDirection, TowerId, TowerList,
+    TowerListLoader, TowerRegistry, bullet_lifetime_multiplier,
+};
+pub use fuse::ArmedFuse;
+pub use lightning::ArcLightningCommand;
+
+use crate::{
+    AppState, GameState, Team,
+    arena::Arena,
+    arena_index::ArenaIndex,
+    bullet::{BULLET_LIFETIME, BULLET_SPEED, SpawnBulletCommand, TowerHit},
+    explosion::{CreateExplosionCommand, ExplosionDamageArea},
+    force::{Attractor, ForceEmitter},
+    game_assets::GameAssets,
+    health::{DiedEvent, Health},
+    loading::LoadingAssets,
+    materials::{BeamMaterial, TowerMaterial},
+};
+
+/// How fast gravity wells pull enemies at their centre, in units per second.
+const GRAVITY_WELL_STRENGTH: f32 = 8.0;
+
+/// How close gravity wells pull enemies, outside the well's own hex and out of reach of the
+/// explosions enemies leave when they die.
+const GRAVITY_WELL_HOLD_DISTANCE: f32 = 2.0;
+
+pub struct TowerPlugin;
+
+impl Plugin for TowerPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<TowerList>()
+            .init_asset_loader::<TowerListLoader>()
+            .init_resource::<TowerRegistry>()
+            .add_event::<TriggerTowerEvent>()
+            .add_systems(Startup, load_towers)
+            .add_systems(Update, log_tower_reloads)
+            .add_systems(
+                OnExit(AppState::InGame),
+                (cleanup_towers, beam::cleanup_beams),
+            )
+            .add_systems(
+                Update,
+                beam::update_beams
+                    .run_if(resource_exists::<Assets<BeamMaterial>>)
+                    .run_if(in_state(AppState::InGame))
+                    .run_if(in_state(GameState::Running)),
+            )
+            .add_systems(
+                Update,
+                update_tower_materials
+                    .run_if(resource_exists::<GameAssets>)
+                    .run_if(in_state(AppState::InGame)),
+            )
+            .add_systems(
+                FixedUpdate,
+                (
+                    trigger_towers.run_if(on_event::<TriggerTowerEvent>),
+                    fuse::update_fuses,
+                    charges::recharge_towers,
+                )
+                    .in_set(TowerSet)
+                    .run_if(in_state(AppState::InGame))
+                    .run_if(in_state(GameState::Running)),
+            );
+    }
+}
+
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TowerSet;
+
+/// Towers take up a hex and trigger an effect when hit by a player bullet or explosion, enemies
+/// that walk into them or explode near them damage them.
+#[derive(Component)]
+pub struct Tower {
+    pub id: TowerId,
+    /// The rotation offset of the `EdgeDirection`, equivalent to `EdgeDirection >> rotation`.
+    pub rotation: u8,
+    /// Starts at 1, raised by merging an identical tower into this one.
+    pub level: u8,
+}
+
+impl Tower {
+    /// Whether building a collected tower onto this one upgrades it, which needs both to be the
+    /// same tower at the same level.
+    pub fn can_merge(&self, id: &TowerId, level: u8) -> bool {
+        self.id == *id && self.level == level && self.level < MAX_TOWER_LEVEL
+    }
+
+    /// The rotation of a tower's mesh, so its texture points in the rotated directions.
+    pub fn mesh_rotation(rotation: u8) -> Quat {
+        let direction = EdgeDirection::FLAT_TOP;
+        let angle = (direction >> rotation).angle_flat() - direction.angle_flat();
+        Quat::from_axis_angle(Vec3::Y, angle)
+    }
+
+    /// The direction a bullet travelling along `incoming` leaves a reflector with mirror `axis`,
+    /// the closest edge direction to the bullet's path mirrored across the rotated axis.
+    pub fn reflect(&self, axis: TowerDirection, incoming: Vec3) -> EdgeDirection {
+        let forward = |direction: EdgeDirection| (bullet_rotation(direction) * Vec3::NEG_Z).xz();
+        let axis = forward(EdgeDirection::from(axis) >> self.rotation);
+        let incoming = incoming.xz().normalize_or_zero();
+        let reflected = 2.0 * incoming.dot(axis) * axis - incoming;
+        EdgeDirection::ALL_DIRECTIONS
+            .into_iter()
+            .max_by(|a, b| {
+                forward(*a)
+                    .dot(reflected)
+                    .total_cmp(&forward(*b).dot(reflected))
+            })
+            .unwrap_or(EdgeDirection::FLAT_TOP)
+    }
+}
+
+/// The rotation of a bullet travelling in `direction`, bullets move along their forward axis.
+pub fn bullet_rotation(direction: EdgeDirection) -> Quat {
+    Quat::from_axis_angle(Vec3::Y, direction.angle_flat() + PI / 2.0)
+}
+
+/// Emit to trigger a tower's effect such as shoot, explode, etc.
+#[derive(Event)]
+pub struct TriggerTowerEvent {
+    /// The tower's ID.
+    pub target: Entity,
+    /// A list of all previous triggers.
+    pub trigger_history: Vec<Entity>,
+}
+
+pub struct PlaceTowerCommand {
+    pub tower: Tower,
+    pub hex: Hex,
+}
+
+impl Command for PlaceTowerCommand {
+    fn apply(self, world: &mut World) {
+        // Ensure the hex isn't already occupied
+        let is_occupied = {
+            let arena_index = world.get_resource::<ArenaIndex>().unwrap();
+            arena_index.is_occupied(&self.hex)
+        };
+        if is_occupied {
+            warn!(hex=?self.hex, "Hex is occupied, tower can't be placed");
+            return;
+        }
+
+        let Some((charges, health)) = world
+            .resource::<TowerRegistry>()
+            .get(world.resource::<Assets<TowerList>>(), &self.tower.id)
+            .map(|definition| {
+                (
+                    TowerCharges::from_definition(definition),
+                    Health::new(definition.health),
+                )
+            })
+        else {
+            warn!(id=%self.tower.id, "Unknown tower, tower can't be placed");
+            return;
+        };
+
+        // Get the world position of the hex
+        let world_pos = {
+            let arena = world.get_resource::<Arena>().unwrap();
+            arena.layout.hex_to_world_pos(self.hex)
+        };
+
+        // Visuals are only available when not running headless
+        let visuals = world.get_resource::<GameAssets>().map(|game_assets| {
+            (
+                Mesh3d(game_assets.tower_mesh.clone()),
+                MeshMaterial3d(game_assets.tower_material(&self.tower.id, self.tower.level)),
+            )
+        });
+
+        // Spawn the tower
+        let transform = Transform::from_xyz(world_pos.x, 0.0, world_pos.y)
+            .with_rotation(Tower::mesh_rotation(self.tower.rotation));
+        let mut tower = world.spawn((self.tower, charges, health, transform));
+        tower.observe(destroy_on_death);
+        if let Some(visuals) = visuals {
+            tower.insert(visuals);
+        }
+        let id = tower.id();
+
+        // Update the index to ensure no other towers are built here
+        let mut arena_index = world.get_resource_mut::<ArenaIndex>().unwrap();
+        arena_index.tower_index.insert(self.hex, id);
+    }
+}
+
+/// Removes a tower destroyed by enemies, freeing up its hex.
+fn destroy_on_death(
+    trigger: Trigger<DiedEvent>,
+    mut commands: Commands,
+    arena: Res<Arena>,
+    mut arena_index: ResMut<ArenaIndex>,
+    q_transform: Query<&Transform, With<Tower>>,
+) {
+    if let Ok(transform) = q_transform.get(trigger.entity) {
+        let hex = arena.layout.world_pos_to_hex(transform.translation.xz());
+        if arena_index.tower_index.get(&hex) == Some(&trigger.entity) {
+            arena_index.tower_index.remove(&hex);
+        }
+    }
+
+    commands.entity(trigger.entity).try_despawn();
+}
+
+fn load_towers(
+    asset_server: Res<AssetServer>,
+    mut tower_registry: ResMut<TowerRegistry>,
+    mut loading_assets: ResMut<LoadingAssets>,
+) {
+    tower_registry.list = asset_server.load("towers/default.towers.ron");
+    loading_assets.push(tower_registry.list.clone().untyped());
+}
+
+fn log_tower_reloads(
+    mut evr_tower_list: EventReader<AssetEvent<TowerList>>,
+    tower_registry: Res<TowerRegistry>,
+) {
+    for event in evr_tower_list.read() {
+        if event.is_modified(&tower_registry.list) {
+            info!("Reloaded tower definitions");
+        }
+    }
+}
+
+fn cleanup_towers(mut commands: Commands, q_tower: Query<Entity, With<Tower>>) {
+    for id in q_tower {
+        commands.entity(id).despawn();
+    }
+}
+
+fn trigger_towers(
+    mut commands: Commands,
+    mut evr_trigger_tower: EventReader<TriggerTowerEvent>,
+    mut q_tower: Query<(&Tower, &Transform, &mut TowerCharges, Has<ArmedFuse>)>,
+    arena: Res<Arena>,
+    tower_registry: Res<TowerRegistry>,
+    tower_lists: Res<Assets<TowerList>>,
+) {
+    for event in evr_trigger_tower.read() {
+        let Ok((tower, tower_transform, mut charges, is_armed)) = q_tower.get_mut(event.target)
+        else {
+            warn!(tower_id=?event.target, "Tower triggered targeting an entity that is not a tower");
+            continue;
+        };
+
+        let Some(definition) = tower_registry.get(&tower_lists, &tower.id) else {
+            warn!(id=%tower.id, "Triggered tower has no definition");
+            continue;
+        };
+
+        if !definition.can_trigger_in_chain(event.target, &event.trigger_history) {
+            continue;
+        }
+
+        // Armed towers ignore triggers until their payload has fired
+        if is_armed || !charges.try_use() {
+            continue;
+        }
+
+        let mut trigger_history = event.trigger_history.clone();
+        trigger_history.push(event.target);
+
+        if let Some(fuse) = definition.fuse() {
+            commands
+                .entity(event.target)
+                .insert(ArmedFuse::new(fuse, trigger_history));
+            continue;
+        }
+
+        run_tower_actions(
+            &mut commands,
+            &arena,
+            tower,
+            tower_transform,
+            definition,
+            &trigger_history,
+        );
+    }
+}
+
+/// Gives armed, recharging and damaged towers their own material so the shader can show their
+/// state, and puts the shared material back once they're back to normal.
+fn update_tower_materials(
+    game_assets: Res<GameAssets>,
+    mut materials: ResMut<Assets<ExtendedMaterial<StandardMaterial, TowerMaterial>>>,
+    mut q_tower: Query<(
+        &Tower,
+        &TowerCharges,
+        &Health,
+        Option<&ArmedFuse>,
+        &mut MeshMaterial3d<ExtendedMaterial<StandardMaterial, TowerMaterial>>,
+    )>,
+) {
+    for (tower, charges, health, fuse, mut material) in q_tower.iter_mut() {
+        // Towers use a material shared with every tower of the same kind until they need their own
+        let shared = game_assets.tower_material(&tower.id, tower.level);
+        if fuse.is_none() && charges.is_full() && health.current >= health.max {
+            // The tower's own material is freed when its handle is dropped
+            if material.0 != shared {
+                material.0 = shared;
+            }
+            continue;
+        }
+
+        if material.0 == shared {
+            let Some(own_material) = materials.get(&shared).cloned() else {
+                continue;
+            };
+            material.0 = materials.add(own_material);
+        }
+
+        if let Some(material) = materials.get_mut(&material.0) {
+            material.extension.fuse = fuse.map_or(0.0, ArmedFuse::fraction_remaining);
+            material.extension.charge = charges.fraction();
+            material.extension.health = health.current as f32 / health.max as f32;
+        }
+    }
+}
+
+/// Runs a tower's actions, `trigger_history` should include the tower.
+fn run_tower_actions(
+    commands: &mut Commands,
+    arena: &Arena,
+    tower: &Tower,
+    tower_transform: &Transform,
+    definition: &TowerDefinition,
+    trigger_history: &[Entity],
+) {
+    let tower_hex = arena
+        .layout
+        .world_pos_to_hex(tower_transform.translation.xz());
+    let lifetime = BULLET_LIFETIME.mul_f32(bullet_lifetime_multiplier(tower.level));
+    for action in definition.actions_at_level(tower.level) {
+        match action {
+            TowerAction::Shoot { direction, damage } => {
+                let direction = EdgeDirection::from(direction) >> tower.rotation;
+                let transform = Transform::from_translation(tower_transform.translation)
+                    .with_rotation(bullet_rotation(direction));
+
+                commands.queue(SpawnBulletCommand {
+                    team: Team::Player,
+                    transform,
+                    damage,
+                    speed: BULLET_SPEED,
+                    lifetime,
+                    tower_hit: TowerHit::Trigger,
+                    trigger_history: trigger_history.to_vec(),
+                });
+            }
+            TowerAction::Explode { range, damage } => {
+                commands.queue(CreateExplosionCommand {
+                    team: Team::Player,
+                    color: LinearRgba::new(0.2, 1.0, 0.2, 1.0),
+                    duration: Duration::from_millis(500),
+                    damage,
+                    damage_area: ExplosionDamageArea::Hex(range),
+                    damage_delay: Duration::from_millis(100),
+                    radius: 1.0 + range as f32 * 1.5,
+                    position: tower_transform.translation.xz(),
+                    strength: 50.0,
+                    strength_modifier: -100.0,
+                    trigger_history: trigger_history.to_vec(),
+                });
+            }
+            TowerAction::Beam { direction, damage } => {
+                commands.queue(FireBeamCommand {
+                    origin: tower_hex,
+                    direction: EdgeDirection::from(direction) >> tower.rotation,
+                    damage,
+                    trigger_history: trigger_history.to_vec(),
+                });
+            }
+            TowerAction::Lightning {
+                range,
+                arcs,
+                damage,
+            } => {
+                commands.queue(ArcLightningCommand {
+                    origin: tower_hex,
+                    range,
+                    arcs,
+                    damage,
+                    trigger_history: trigger_history.to_vec(),
+                });
+            }
+            TowerAction::GravityWell { range, seconds } => {
+                commands.spawn((
+                    Attractor::new(Duration::from_secs_f32(seconds), GRAVITY_WELL_HOLD_DISTANCE),
+                    ForceEmitter {
+                        strength: GRAVITY_WELL_STRENGTH,
+                        radius: 1.0 + range as f32 * 1.5,
+                    },
+                    Transform::from_translation(tower_transform.translation),
+                ));
+            }
+            // The fuse delays the other actions, see `ArmedFuse`
+            TowerAction::Fuse { .. } => {}
+            // Reflectors only act on bullets, see `check_tower_collision`
+            TowerAction::Reflect { .. } => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn travelling(direction: EdgeDirection) -> Vec3 {
+        bullet_rotation(direction) * Vec3::NEG_Z
+    }
+
+    #[test]
+    fn reflect_mirrors_across_rotated_axis() {
+        let mirror = |rotation| Tower {
+            id: TowerId::from("mirror"),
+            rotation,
+            level: 1,
+        };
+
+        // Bullets glance off a vertical mirror
+        assert_eq!(
+            mirror(0).reflect(
+                TowerDirection::Top,
+                travelling(EdgeDirection::FLAT_TOP_RIGHT)
+            ),
+            EdgeDirection::FLAT_TOP_LEFT
+        );
+        // Bullets along the mirror pass straight through
+        assert_eq!(
+            mirror(0).reflect(TowerDirection::Top, travelling(EdgeDirection::FLAT_BOTTOM)),
+            EdgeDirection::FLAT_BOTTOM
+        );
+        // Rotating the tower rotates the mirror
+        assert_eq!(
+            mirror(1).reflect(TowerDirection::Top, travelling(EdgeDirection::FLAT_BOTTOM)),
+            EdgeDirection::FLAT_TOP_LEFT
+        );
+    }
+}
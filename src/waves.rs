@@ -1,357 +1,178 @@
 use bevy::prelude::*;
 use hexx::Hex;
-use rand::seq::IndexedRandom;
+use rand::{Rng, seq::IndexedRandom};
+use serde::{Deserialize, Serialize};
 
 use crate::{
     AppState, GameState,
     arena::Arena,
-    enemy::{Enemy, EnemySet, SpawnEnemyCommand},
-    tower::TowerKind,
+    building::{BuildingSettings, CollectedTower},
+    difficulty::Difficulty,
+    enemy::{Enemy, EnemyArchetype, EnemySet, SpawnEnemyCommand},
+    loading::LoadingAssets,
+    replay::{ReplayAction, ReplayRecorder},
+    rng::RunRng,
+    ron_list::{RonList, RonListLoader},
+    tower::{TowerId, TowerList, TowerRegistry},
 };
 
-pub const WAVES: &[Wave] = &[
-    // Wave 1
-    Wave {
-        stages: &[
-            WaveStage {
-                enemies: 2,
-                remaining_threshold: 0,
-            },
-            WaveStage {
-                enemies: 3,
-                remaining_threshold: 1,
-            },
-            WaveStage {
-                enemies: 4,
-                remaining_threshold: 2,
-            },
-        ],
-        reward: WaveReward {
-            options: 1,
-            pool: &[
-                TowerKind::Bullet2,
-                TowerKind::Bullet3,
-                TowerKind::Explosion1,
-            ],
-        },
-    },
-    // Wave 2
-    Wave {
-        stages: &[
-            WaveStage {
-                enemies: 4,
-                remaining_threshold: 0,
-            },
-            WaveStage {
-                enemies: 6,
-                remaining_threshold: 2,
-            },
-            WaveStage {
-                enemies: 6,
-                remaining_threshold: 4,
-            },
-            WaveStage {
-                enemies: 6,
-                remaining_threshold: 4,
-            },
-        ],
-        reward: WaveReward {
-            options: 2,
-            pool: &[
-                TowerKind::Bullet2,
-                TowerKind::Bullet3,
-                TowerKind::Bullet4,
-                TowerKind::Explosion1,
-            ],
-        },
-    },
-    // Wave 3
-    Wave {
-        stages: &[
-            WaveStage {
-                enemies: 6,
-                remaining_threshold: 0,
-            },
-            WaveStage {
-                enemies: 8,
-                remaining_threshold: 4,
-            },
-            WaveStage {
-                enemies: 8,
-                remaining_threshold: 6,
-            },
-            WaveStage {
-                enemies: 10,
-                remaining_threshold: 8,
-            },
-        ],
-        reward: WaveReward {
-            options: 2,
-            pool: &[
-                TowerKind::Bullet3,
-                TowerKind::Bullet4,
-                TowerKind::Explosion1,
-            ],
-        },
-    },
-    // Wave 4
-    Wave {
-        stages: &[
-            WaveStage {
-                enemies: 8,
-                remaining_threshold: 0,
-            },
-            WaveStage {
-                enemies: 10,
-                remaining_threshold: 6,
-            },
-            WaveStage {
-                enemies: 12,
-                remaining_threshold: 8,
-            },
-            WaveStage {
-                enemies: 12,
-                remaining_threshold: 10,
-            },
-            WaveStage {
-                enemies: 14,
-                remaining_threshold: 12,
-            },
-            WaveStage {
-                enemies: 16,
-                remaining_threshold: 12,
-            },
-        ],
-        reward: WaveReward {
-            options: 3,
-            pool: &[
-                TowerKind::Bullet3,
-                TowerKind::Bullet4,
-                TowerKind::Bullet6,
-                TowerKind::Explosion1,
-                TowerKind::Explosion2,
-            ],
-        },
-    },
-    // Wave 5
-    Wave {
-        stages: &[
-            WaveStage {
-                enemies: 10,
-                remaining_threshold: 0,
-            },
-            WaveStage {
-                enemies: 12,
-                remaining_threshold: 8,
-            },
-            WaveStage {
-                enemies: 14,
-                remaining_threshold: 10,
-            },
-            WaveStage {
-                enemies: 16,
-                remaining_threshold: 12,
-            },
-            WaveStage {
-                enemies: 18,
-                remaining_threshold: 14,
-            },
-            WaveStage {
-                enemies: 20,
-                remaining_threshold: 16,
-            },
-        ],
-        reward: WaveReward {
-            options: 3,
-            pool: &[
-                TowerKind::Bullet3,
-                TowerKind::Bullet4,
-                TowerKind::Bullet6,
-                TowerKind::Explosion2,
-                TowerKind::Explosion3,
-            ],
-        },
-    },
-    // Wave 6
-    Wave {
-        stages: &[
-            WaveStage {
-                enemies: 14,
-                remaining_threshold: 0,
-            },
-            WaveStage {
-                enemies: 16,
-                remaining_threshold: 10,
-            },
-            WaveStage {
-                enemies: 18,
-                remaining_threshold: 12,
-            },
-            WaveStage {
-                enemies: 20,
-                remaining_threshold: 14,
-            },
-            WaveStage {
-                enemies: 22,
-                remaining_threshold: 16,
-            },
-            WaveStage {
-                enemies: 24,
-                remaining_threshold: 18,
-            },
-            WaveStage {
-                enemies: 26,
-                remaining_threshold: 18,
-            },
-        ],
-        reward: WaveReward {
-            options: 3,
-            pool: &[
-                TowerKind::Bullet3,
-                TowerKind::Bullet4,
-                TowerKind::Bullet6,
-                TowerKind::Explosion2,
-                TowerKind::Explosion3,
-            ],
-        },
-    },
-    // Wave 7
-    Wave {
-        stages: &[
-            WaveStage {
-                enemies: 18,
-                remaining_threshold: 0,
-            },
-            WaveStage {
-                enemies: 20,
-                remaining_threshold: 14,
-            },
-            WaveStage {
-                enemies: 22,
-                remaining_threshold: 16,
-            },
-            WaveStage {
-                enemies: 24,
-                remaining_threshold: 18,
-            },
-            WaveStage {
-                enemies: 26,
-                remaining_threshold: 20,
-            },
-            WaveStage {
-                enemies: 32,
-                remaining_threshold: 22,
-            },
-            WaveStage {
-                enemies: 34,
-                remaining_threshold: 22,
-            },
-            WaveStage {
-                enemies: 38,
-                remaining_threshold: 26,
-            },
-        ],
-        reward: WaveReward {
-            options: 3,
-            pool: &[
-                TowerKind::Bullet3,
-                TowerKind::Bullet4,
-                TowerKind::Bullet6,
-                TowerKind::Explosion2,
-                TowerKind::Explosion3,
-            ],
-        },
-    },
-    // Wave 8
-    Wave {
-        stages: &[
-            WaveStage {
-                enemies: 22,
-                remaining_threshold: 0,
-            },
-            WaveStage {
-                enemies: 24,
-                remaining_threshold: 18,
-            },
-            WaveStage {
-                enemies: 26,
-                remaining_threshold: 20,
-            },
-            WaveStage {
-                enemies: 28,
-                remaining_threshold: 22,
-            },
-            WaveStage {
-                enemies: 34,
-                remaining_threshold: 24,
-            },
-            WaveStage {
-                enemies: 36,
-                remaining_threshold: 26,
-            },
-            WaveStage {
-                enemies: 38,
-                remaining_threshold: 26,
-            },
-            WaveStage {
-                enemies: 42,
-                remaining_threshold: 30,
-            },
-            WaveStage {
-                enemies: 50,
-                remaining_threshold: 40,
-            },
-        ],
-        reward: WaveReward {
-            options: 3,
-            pool: &[
-                TowerKind::Bullet3,
-                TowerKind::Bullet4,
-                TowerKind::Bullet6,
-                TowerKind::Explosion2,
-                TowerKind::Explosion3,
-            ],
-        },
-    },
-];
+/// The wave definitions for a run, loaded from a `.waves.ron` file by `WaveListLoader`.
+#[derive(Asset, TypePath, Deserialize, Debug)]
+pub struct WaveList {
+    pub waves: Vec<Wave>,
+}
 
+#[derive(Deserialize, Debug)]
 pub struct Wave {
-    pub stages: &'static [WaveStage],
+    pub stages: Vec<WaveStage>,
     pub reward: WaveReward,
 }
 
+#[derive(Deserialize, Debug)]
 pub struct WaveStage {
     /// How many enemies to spawn, they all spawn at once around the edge of the arena.
     pub enemies: usize,
     /// Enemies wont spawn until there is at most this many enemies remaining.
     pub remaining_threshold: usize,
+    /// Each enemy is a random one of these archetypes, list an archetype more than once to spawn
+    /// more of it.
+    #[serde(default = "default_archetypes")]
+    pub archetypes: Vec<EnemyArchetype>,
 }
 
+fn default_archetypes() -> Vec<EnemyArchetype> {
+    vec![EnemyArchetype::Grunt]
+}
+
+#[derive(Deserialize, Debug)]
 pub struct WaveReward {
     /// The number of options that will appear for the player to select from.
     pub options: usize,
     /// Rewards will be randomly selected from this pool.
-    pub pool: &'static [TowerKind],
+    pub pool: Vec<TowerId>,
 }
 
 impl WaveReward {
-    pub fn get_random_options(&self) -> Vec<TowerKind> {
-        let mut rng = rand::rng();
+    pub fn get_random_options(&self, rng: &mut impl Rng) -> Vec<TowerId> {
         self.pool
-            .choose_multiple(&mut rng, self.options)
+            .choose_multiple(rng, self.options)
             .map(Clone::clone)
             .collect()
     }
 }
 
+/// Generates the waves played after the scripted ones in `GameMode::Endless`.
+///
+/// `level` starts at 1 for the first generated wave, every level has more enemies that overlap
+/// more between stages. Rewards are picked from `pool`.
+pub fn generate_endless_wave(level: usize, pool: Vec<TowerId>) -> Wave {
+    let stage_count = (9 + level / 2).min(16);
+    let stages = (0..stage_count)
+        .map(|stage| {
+            let enemies = 24 + level * 4 + stage * 3;
+            WaveStage {
+                enemies,
+                // The second stage only spawns once the first is cleared
+                remaining_threshold: if stage == 0 { 0 } else { enemies * 3 / 4 },
+                archetypes: endless_archetypes(level),
+            }
+        })
+        .collect();
+
+    Wave {
+        stages,
+        reward: WaveReward {
+            options: (3 + level / 5).min(pool.len()),
+            pool,
+        },
+    }
+}
+
+/// The archetypes spawned by generated waves, tougher ones join in as the levels go up.
+fn endless_archetypes(level: usize) -> Vec<EnemyArchetype> {
+    let mut archetypes = vec![
+        EnemyArchetype::Grunt,
+        EnemyArchetype::Grunt,
+        EnemyArchetype::Runner,
+    ];
+    if level >= 3 {
+        archetypes.push(EnemyArchetype::Brute);
+    }
+    if level >= 4 {
+        archetypes.push(EnemyArchetype::Shooter);
+    }
+    if level >= 5 {
+        archetypes.push(EnemyArchetype::Splitter);
+    }
+    if level >= 7 {
+        archetypes.push(EnemyArchetype::Charger);
+    }
+    archetypes
+}
+
+impl RonList for WaveList {
+    const NAME: &'static str = "waves";
+    const EXTENSIONS: &'static [&'static str] = &["waves.ron"];
+
+    /// Checks that every wave can be played, the error names the offending wave and stage.
+    fn validate(&self) -> Result<(), String> {
+        if self.waves.is_empty() {
+            return Err("there must be at least one wave".to_string());
+        }
+
+        for (wave_index, wave) in self.waves.iter().enumerate() {
+            let wave_display = wave_index + 1;
+            if wave.stages.is_empty() {
+                return Err(format!("wave {wave_display} has no stages"));
+            }
+            for (stage_index, stage) in wave.stages.iter().enumerate() {
+                if stage.enemies == 0 {
+                    return Err(format!(
+                        "wave {wave_display}, stage {}: must spawn at least one enemy",
+                        stage_index + 1
+                    ));
+                }
+                if stage.archetypes.is_empty() {
+                    return Err(format!(
+                        "wave {wave_display}, stage {}: must have at least one archetype",
+                        stage_index + 1
+                    ));
+                }
+            }
+            if wave.reward.options == 0 {
+                return Err(format!(
+                    "wave {wave_display}, reward: must offer at least one option"
+                ));
+            }
+            if wave.reward.pool.len() < wave.reward.options {
+                return Err(format!(
+                    "wave {wave_display}, reward: offers {} options but the pool only has {} towers",
+                    wave.reward.options,
+                    wave.reward.pool.len()
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub type WaveListLoader = RonListLoader<WaveList>;
+
 pub struct WavePlugin;
 
 impl Plugin for WavePlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<WaveManager>()
+        app.init_asset::<WaveList>()
+            .init_asset_loader::<WaveListLoader>()
+            .init_resource::<WaveManager>()
+            .init_resource::<GameMode>()
             .add_event::<WaveStartedEvent>()
             .add_event::<WaveStageStartedEvent>()
+            .add_systems(Startup, load_waves)
+            .add_systems(Update, log_wave_reloads)
             .add_systems(OnEnter(AppState::InGame), setup_waves.in_set(EnemySet))
+            .add_systems(OnEnter(GameState::RewardSelect), roll_reward_options)
             .add_systems(
                 OnTransition {
                     exited: GameState::RewardSelect,
@@ -360,30 +181,59 @@ impl Plugin for WavePlugin {
                 next_wave,
             )
             .add_systems(
-                Update,
+                FixedUpdate,
                 (
                     update_wave_progress,
                     spawn_stage.run_if(on_event::<WaveStageStartedEvent>),
                 )
+                    .in_set(EnemySet)
                     .run_if(in_state(AppState::InGame))
                     .run_if(in_state(GameState::Running)),
             );
     }
 }
 
+/// How a run progresses once the scripted waves run out.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GameMode {
+    /// The run ends after the last scripted wave.
+    #[default]
+    Standard,
+    /// Waves keep getting generated until the player dies.
+    Endless,
+}
+
+impl GameMode {
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameMode::Standard => "Standard",
+            GameMode::Endless => "Endless",
+        }
+    }
+}
+
 #[derive(Resource)]
 pub struct WaveManager {
+    /// The wave definitions used for runs.
+    pub waves: Handle<WaveList>,
     wave: usize,
     stage: usize,
     update_timer: Timer,
+    /// The current wave when it's past the scripted waves in `GameMode::Endless`.
+    generated_wave: Option<Wave>,
+    /// The rewards the player can choose from after the current wave.
+    reward_options: Vec<TowerId>,
 }
 
 impl Default for WaveManager {
     fn default() -> Self {
         Self {
+            waves: Handle::default(),
             wave: 0,
             stage: 0,
             update_timer: Timer::from_seconds(1.0, TimerMode::Repeating),
+            generated_wave: None,
+            reward_options: Vec::new(),
         }
     }
 }
@@ -399,8 +249,56 @@ impl WaveManager {
         self.stage + 1
     }
 
-    pub fn wave_reward(&self) -> &'static WaveReward {
-        &WAVES[self.wave].reward
+    /// The definition of the current wave, `None` if the waves aren't loaded or a reload removed
+    /// the current wave.
+    pub fn current_wave<'a>(&'a self, wave_lists: &'a Assets<WaveList>) -> Option<&'a Wave> {
+        wave_lists
+            .get(&self.waves)
+            .and_then(|wave_list| wave_list.waves.get(self.wave))
+            .or(self.generated_wave.as_ref())
+    }
+
+    /// The rewards offered at the end of the current wave.
+    pub fn reward_options(&self) -> &[TowerId] {
+        &self.reward_options
+    }
+}
+
+/// Adds one of the offered rewards to the player's towers and continues to the next wave.
+///
+/// All reward choices go through this command so they can be recorded and replayed.
+pub struct ChooseRewardCommand {
+    /// The index of the chosen reward in `WaveManager::reward_options`.
+    pub option: usize,
+}
+
+impl Command for ChooseRewardCommand {
+    fn apply(self, world: &mut World) -> () {
+        let Some(reward) = world
+            .resource::<WaveManager>()
+            .reward_options
+            .get(self.option)
+            .cloned()
+        else {
+            warn!(
+                option = self.option,
+                "Tried choosing a reward that wasn't offered"
+            );
+            return;
+        };
+
+        world
+            .resource_mut::<BuildingSettings>()
+            .add(CollectedTower::new(reward));
+        world
+            .resource_mut::<NextState<GameState>>()
+            .set(GameState::Running);
+
+        if let Some(mut recorder) = world.get_resource_mut::<ReplayRecorder>() {
+            recorder.record(ReplayAction::ChooseReward {
+                option: self.option,
+            });
+        }
     }
 }
 
@@ -418,6 +316,26 @@ pub struct WaveStageStartedEvent {
     pub stage: usize,
 }
 
+fn load_waves(
+    asset_server: Res<AssetServer>,
+    mut wave_manager: ResMut<WaveManager>,
+    mut loading_assets: ResMut<LoadingAssets>,
+) {
+    wave_manager.waves = asset_server.load("waves/default.waves.ron");
+    loading_assets.push(wave_manager.waves.clone().untyped());
+}
+
+fn log_wave_reloads(
+    mut evr_wave_list: EventReader<AssetEvent<WaveList>>,
+    wave_manager: Res<WaveManager>,
+) {
+    for event in evr_wave_list.read() {
+        if event.is_modified(&wave_manager.waves) {
+            info!("Reloaded wave definitions");
+        }
+    }
+}
+
 fn setup_waves(
     mut wave_manager: ResMut<WaveManager>,
     mut evw_wave_started: EventWriter<WaveStartedEvent>,
@@ -425,6 +343,7 @@ fn setup_waves(
 ) {
     wave_manager.wave = 0;
     wave_manager.stage = 0;
+    wave_manager.generated_wave = None;
     evw_wave_started.write(WaveStartedEvent {
         wave: wave_manager.wave_display(),
     });
@@ -434,20 +353,62 @@ fn setup_waves(
     });
 }
 
-fn spawn_stage(mut commands: Commands, arena: Res<Arena>, wave_manager: Res<WaveManager>) {
-    let wave_stage = &WAVES[wave_manager.wave].stages[wave_manager.stage];
+pub fn roll_reward_options(
+    mut wave_manager: ResMut<WaveManager>,
+    mut rng: ResMut<RunRng>,
+    wave_lists: Res<Assets<WaveList>>,
+) {
+    let Some(wave) = wave_manager.current_wave(&wave_lists) else {
+        warn!(
+            wave = wave_manager.wave_display(),
+            "Missing wave definition"
+        );
+        wave_manager.reward_options = Vec::new();
+        return;
+    };
+    let reward_options = wave.reward.get_random_options(&mut rng.rewards);
+    wave_manager.reward_options = reward_options;
+}
+
+fn spawn_stage(
+    mut commands: Commands,
+    arena: Res<Arena>,
+    wave_manager: Res<WaveManager>,
+    mut rng: ResMut<RunRng>,
+    wave_lists: Res<Assets<WaveList>>,
+    difficulty: Res<Difficulty>,
+) {
+    let Some(wave_stage) = wave_manager
+        .current_wave(&wave_lists)
+        .and_then(|wave| wave.stages.get(wave_manager.stage))
+    else {
+        warn!(
+            wave = wave_manager.wave_display(),
+            stage = wave_manager.stage_display(),
+            "Missing wave stage definition"
+        );
+        return;
+    };
 
     // Get the hexes around the edge of the arena to distribute randomly among them
     let mut hexes: Vec<Hex> = Hex::ZERO.ring(Arena::RADIUS).collect();
 
-    for _ in 0..wave_stage.enemies {
+    for _ in 0..difficulty.scale_enemy_count(wave_stage.enemies) {
         if hexes.len() == 0 {
             // Refill hexes and continue
             hexes = Hex::ZERO.ring(Arena::RADIUS).collect();
         }
-        let index = rand::random_range(0..hexes.len() - 1);
+        let index = rng.spawning.random_range(0..hexes.len());
         let hex = hexes.swap_remove(index);
-        commands.queue(SpawnEnemyCommand::new(arena.layout.hex_to_world_pos(hex)));
+        let archetype = wave_stage
+            .archetypes
+            .choose(&mut rng.spawning)
+            .copied()
+            .unwrap_or_default();
+        commands.queue(SpawnEnemyCommand {
+            position: arena.layout.hex_to_world_pos(hex),
+            archetype,
+        });
     }
 }
 
@@ -458,7 +419,14 @@ fn update_wave_progress(
     mut wave_manager: ResMut<WaveManager>,
     mut evw_wave_stage_started: EventWriter<WaveStageStartedEvent>,
     q_enemies: Query<Entity, With<Enemy>>,
+    wave_lists: Res<Assets<WaveList>>,
+    game_mode: Res<GameMode>,
+    difficulty: Res<Difficulty>,
 ) {
+    let Some(wave_list) = wave_lists.get(&wave_manager.waves) else {
+        return;
+    };
+
     // Only check for updates once per timer
     wave_manager.update_timer.tick(time.delta());
     if !wave_manager.update_timer.just_finished() {
@@ -468,14 +436,23 @@ fn update_wave_progress(
     // Get a total count of enemies
     let remaining_enemies = q_enemies.iter().len();
 
-    let wave = &WAVES[wave_manager.wave];
-    if wave.stages.len() <= wave_manager.stage + 1 {
+    let Some(wave) = wave_manager.current_wave(&wave_lists) else {
+        // A reload removed the current wave, treat it as the last one
+        next_app_state.set(AppState::GameOver);
+        return;
+    };
+    let is_last_stage = wave.stages.len() <= wave_manager.stage + 1;
+    let remaining_threshold = wave.stages.get(wave_manager.stage).map_or(0, |stage| {
+        difficulty.scale_enemy_count(stage.remaining_threshold)
+    });
+
+    if is_last_stage {
         // This is the last stage
         if remaining_enemies > 0 {
             return;
         }
 
-        if WAVES.len() <= wave_manager.wave + 1 {
+        if *game_mode == GameMode::Standard && wave_list.waves.len() <= wave_manager.wave + 1 {
             // There are no more waves, end the game
             next_app_state.set(AppState::GameOver);
             return;
@@ -484,8 +461,7 @@ fn update_wave_progress(
         next_game_state.set(GameState::RewardSelect);
     } else {
         // There are stages remaining
-        let stage = &wave.stages[wave_manager.stage];
-        if remaining_enemies > stage.remaining_threshold {
+        if remaining_enemies > remaining_threshold {
             return;
         }
         wave_manager.stage += 1;
@@ -501,9 +477,33 @@ fn next_wave(
     mut wave_manager: ResMut<WaveManager>,
     mut evw_wave_started: EventWriter<WaveStartedEvent>,
     mut evw_wave_stage_started: EventWriter<WaveStageStartedEvent>,
+    wave_lists: Res<Assets<WaveList>>,
+    tower_registry: Res<TowerRegistry>,
+    tower_lists: Res<Assets<TowerList>>,
+    game_mode: Res<GameMode>,
 ) {
     wave_manager.wave += 1;
     wave_manager.stage = 0;
+    wave_manager.generated_wave = None;
+
+    let scripted_waves = wave_lists
+        .get(&wave_manager.waves)
+        .map_or(0, |wave_list| wave_list.waves.len());
+    if *game_mode == GameMode::Endless && wave_manager.wave >= scripted_waves {
+        // Every tower can be offered once the scripted waves are done
+        let pool = tower_lists
+            .get(&tower_registry.list)
+            .map(|tower_list| {
+                tower_list
+                    .towers
+                    .iter()
+                    .map(|tower| tower.id.clone())
+                    .collect()
+            })
+            .unwrap_or_default();
+        let level = wave_manager.wave + 1 - scripted_waves;
+        wave_manager.generated_wave = Some(generate_endless_wave(level, pool));
+    }
 
     evw_wave_started.write(WaveStartedEvent {
         wave: wave_manager.wave_display(),
@@ -513,3 +513,63 @@ fn next_wave(
         stage: wave_manager.stage_display(),
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn endless_waves_are_valid() {
+        let pool = vec![TowerId::from("bullet2"), TowerId::from("explosion1")];
+        let waves = (1..=50)
+            .map(|level| generate_endless_wave(level, pool.clone()))
+            .collect();
+
+        assert_eq!(WaveList { waves }.validate(), Ok(()));
+    }
+
+    #[test]
+    fn endless_waves_get_harder() {
+        let enemies = |level| -> usize {
+            generate_endless_wave(level, Vec::new())
+                .stages
+                .iter()
+                .map(|stage| stage.enemies)
+                .sum()
+        };
+
+        assert!(enemies(2) > enemies(1));
+        assert!(enemies(20) > enemies(10));
+    }
+
+    #[test]
+    fn stages_can_spawn_more_enemies_than_the_edge_has_hexes() {
+        use bevy::ecs::system::RunSystemOnce;
+
+        let enemies = Hex::ZERO.ring(Arena::RADIUS).count() * 2 + 1;
+        let mut world = World::new();
+        world.insert_resource(Arena { layout: default() });
+        world.insert_resource(WaveManager {
+            generated_wave: Some(Wave {
+                stages: vec![WaveStage {
+                    enemies,
+                    remaining_threshold: 0,
+                    archetypes: default_archetypes(),
+                }],
+                reward: WaveReward {
+                    options: 0,
+                    pool: Vec::new(),
+                },
+            }),
+            ..default()
+        });
+        world.insert_resource(RunRng::new(0));
+        world.init_resource::<Assets<WaveList>>();
+        world.init_resource::<Difficulty>();
+
+        world.run_system_once(spawn_stage).unwrap();
+
+        let mut query = world.query::<&Enemy>();
+        assert_eq!(query.iter(&world).count(), enemies);
+    }
+}
@@ -0,0 +1,754 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+use chain_reaction::{
+    AppState, HeadlessGamePlugins, Team,
+    arena::Arena,
+    arena_index::ArenaIndex,
+    building::{BuildTowerCommand, BuildingSettings, CollectedTower, PickUpTowerCommand},
+    enemy::{Enemy, EnemyArchetype, SpawnEnemyCommand},
+    explosion::Explosion,
+    health::{DamageEvent, Health},
+    player::{Player, PlayerInput},
+    replay::{ReplayPlayback, ReplayRecorder},
+    rng::NextRunSeed,
+    score::PlayerScore,
+    tower::{
+        ArcLightningCommand, ArmedFuse, PlaceTowerCommand, Tower, TowerCharges, TowerId,
+        TriggerTowerEvent,
+    },
+};
+use hexx::{EdgeDirection, Hex};
+
+fn headless_app() -> App {
+    headless_app_with_step(Duration::from_millis(50))
+}
+
+/// Creates a headless app where each update advances time by `step`.
+fn headless_app_with_step(step: Duration) -> App {
+    let mut app = App::new();
+    app.add_plugins(HeadlessGamePlugins)
+        .insert_resource(TimeUpdateStrategy::ManualDuration(step));
+    app
+}
+
+/// Updates the app until it enters `state`, assets load in the background so this can take a few
+/// updates.
+fn update_until_state(app: &mut App, state: AppState) {
+    for _ in 0..1000 {
+        if *app.world().resource::<State<AppState>>().get() == state {
+            return;
+        }
+        app.update();
+        std::thread::sleep(Duration::from_millis(1));
+    }
+    panic!("timed out waiting for {state:?}");
+}
+
+/// Creates a headless app that has entered `AppState::InGame`, each update advances time by 50ms.
+fn in_game_app() -> App {
+    in_game_app_with_seed(None)
+}
+
+fn in_game_app_with_seed(seed: Option<u64>) -> App {
+    start_run(headless_app(), seed)
+}
+
+fn start_run(mut app: App, seed: Option<u64>) -> App {
+    update_until_state(&mut app, AppState::Menu);
+
+    app.world_mut().resource_mut::<NextRunSeed>().0 = seed;
+    app.world_mut()
+        .resource_mut::<NextState<AppState>>()
+        .set(AppState::InGame);
+    app.update();
+
+    app
+}
+
+fn count<F: bevy::ecs::query::QueryFilter>(app: &mut App) -> usize {
+    let mut query = app.world_mut().query_filtered::<Entity, F>();
+    query.iter(app.world()).count()
+}
+
+/// Places a level 1 tower and returns its entity.
+fn place_tower(app: &mut App, id: &str, hex: Hex, rotation: u8) -> Entity {
+    PlaceTowerCommand {
+        tower: Tower {
+            id: TowerId::from(id),
+            rotation,
+            level: 1,
+        },
+        hex,
+    }
+    .apply(app.world_mut());
+    app.world().resource::<ArenaIndex>().tower_index[&hex]
+}
+
+/// Spawns an enemy in the middle of `hex` and returns its entity.
+fn spawn_enemy(app: &mut App, hex: Hex, archetype: EnemyArchetype) -> Entity {
+    let position = app.world().resource::<Arena>().layout.hex_to_world_pos(hex);
+    let wave_enemies = enemy_ids(app);
+    SpawnEnemyCommand {
+        position,
+        archetype,
+    }
+    .apply(app.world_mut());
+    enemy_ids(app)
+        .into_iter()
+        .find(|id| !wave_enemies.contains(id))
+        .unwrap()
+}
+
+#[test]
+fn starts_game_and_spawns_first_stage() {
+    let mut app = in_game_app();
+    app.update();
+
+    assert_eq!(
+        *app.world().resource::<State<AppState>>().get(),
+        AppState::InGame
+    );
+    assert_eq!(count::<With<Player>>(&mut app), 1);
+    assert_eq!(count::<With<Enemy>>(&mut app), 2);
+}
+
+fn enemy_ids(app: &mut App) -> Vec<Entity> {
+    let mut query = app.world_mut().query_filtered::<Entity, With<Enemy>>();
+    query.iter(app.world()).collect()
+}
+
+fn enemy_positions(app: &mut App) -> Vec<Vec3> {
+    let mut query = app.world_mut().query_filtered::<&Transform, With<Enemy>>();
+    let mut positions: Vec<Vec3> = query.iter(app.world()).map(|t| t.translation).collect();
+    positions.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.z.total_cmp(&b.z)));
+    positions
+}
+
+#[test]
+fn same_seed_spawns_same_enemies() {
+    let mut first = in_game_app_with_seed(Some(1234));
+    let mut second = in_game_app_with_seed(Some(1234));
+    first.update();
+    second.update();
+
+    assert_eq!(enemy_positions(&mut first), enemy_positions(&mut second));
+}
+
+#[test]
+fn tower_explosion_kills_enemy_and_scores() {
+    let mut app = in_game_app();
+
+    let hex = Hex::new(5, 0);
+    let position = app.world().resource::<Arena>().layout.hex_to_world_pos(hex);
+
+    PlaceTowerCommand {
+        tower: Tower {
+            id: TowerId::from("explosion1"),
+            rotation: 0,
+            level: 1,
+        },
+        hex,
+    }
+    .apply(app.world_mut());
+    SpawnEnemyCommand::new(position).apply(app.world_mut());
+    app.update();
+
+    let tower_id = *app
+        .world()
+        .resource::<ArenaIndex>()
+        .tower_index
+        .get(&hex)
+        .unwrap();
+    app.world_mut().send_event(TriggerTowerEvent {
+        target: tower_id,
+        trigger_history: Vec::new(),
+    });
+
+    for _ in 0..10 {
+        app.update();
+    }
+
+    let score = app.world().resource::<PlayerScore>();
+    assert_eq!(score.score, 2);
+    assert_eq!(score.highest_chain, 1);
+}
+
+#[test]
+fn laser_beam_hits_enemies_and_towers_in_line() {
+    let mut app = in_game_app_with_seed(Some(1));
+
+    let laser_hex = Hex::new(5, 0);
+    let direction = EdgeDirection::FLAT_TOP;
+    let bomb_hex = laser_hex + direction * 6;
+    let laser_id = place_tower(&mut app, "laser", laser_hex, 0);
+    place_tower(&mut app, "explosion1", bomb_hex, 0);
+    // One enemy in the beam, the other only next to the bomb the beam triggers
+    let enemies = [
+        spawn_enemy(&mut app, laser_hex + direction * 3, EnemyArchetype::Grunt),
+        spawn_enemy(
+            &mut app,
+            bomb_hex + EdgeDirection::FLAT_TOP_RIGHT,
+            EnemyArchetype::Grunt,
+        ),
+    ];
+    app.update();
+
+    app.world_mut().send_event(TriggerTowerEvent {
+        target: laser_id,
+        trigger_history: Vec::new(),
+    });
+
+    for _ in 0..10 {
+        app.update();
+    }
+
+    for id in enemies {
+        assert!(app.world().get_entity(id).is_err());
+    }
+    assert_eq!(app.world().resource::<PlayerScore>().highest_chain, 2);
+}
+
+#[test]
+fn lightning_arcs_to_nearest_untriggered_towers() {
+    let mut app = in_game_app_with_seed(Some(1));
+
+    let coil_hex = Hex::new(5, 0);
+    let coil_id = place_tower(&mut app, "lightning", coil_hex, 0);
+    let near_id = place_tower(
+        &mut app,
+        "explosion1",
+        coil_hex + EdgeDirection::FLAT_TOP * 2,
+        0,
+    );
+    place_tower(
+        &mut app,
+        "explosion1",
+        coil_hex + EdgeDirection::FLAT_BOTTOM * 3,
+        0,
+    );
+    // Mirrors only reflect bullets, so the nearer mirror doesn't use up the arc
+    place_tower(&mut app, "mirror", coil_hex + EdgeDirection::FLAT_BOTTOM, 0);
+
+    ArcLightningCommand {
+        origin: coil_hex,
+        range: 4,
+        arcs: 1,
+        damage: 1,
+        trigger_history: vec![coil_id],
+    }
+    .apply(app.world_mut());
+
+    let events = app.world().resource::<Events<TriggerTowerEvent>>();
+    let targets: Vec<Entity> = events
+        .get_cursor()
+        .read(events)
+        .map(|event| event.target)
+        .collect();
+    assert_eq!(targets, vec![near_id]);
+}
+
+#[test]
+fn lightning_arcs_to_towers_with_charges_left_in_the_chain() {
+    let mut app = in_game_app_with_seed(Some(1));
+
+    let coil_hex = Hex::new(5, 0);
+    let coil_id = place_tower(&mut app, "lightning", coil_hex, 0);
+    // Twin shots have 2 charges, so they can be triggered a second time
+    let twin_id = place_tower(
+        &mut app,
+        "bullet2",
+        coil_hex + EdgeDirection::FLAT_TOP * 2,
+        0,
+    );
+    let bomb_id = place_tower(
+        &mut app,
+        "explosion1",
+        coil_hex + EdgeDirection::FLAT_BOTTOM,
+        0,
+    );
+
+    ArcLightningCommand {
+        origin: coil_hex,
+        range: 4,
+        arcs: 3,
+        damage: 1,
+        trigger_history: vec![twin_id, bomb_id, coil_id],
+    }
+    .apply(app.world_mut());
+
+    let events = app.world().resource::<Events<TriggerTowerEvent>>();
+    let targets: Vec<Entity> = events
+        .get_cursor()
+        .read(events)
+        .map(|event| event.target)
+        .collect();
+    assert_eq!(targets, vec![twin_id]);
+}
+
+#[test]
+fn fuse_delays_tower_payload() {
+    let mut app = in_game_app_with_seed(Some(1));
+
+    let tower_id = place_tower(&mut app, "fuse", Hex::new(5, 0), 0);
+    app.world_mut().send_event(TriggerTowerEvent {
+        target: tower_id,
+        trigger_history: Vec::new(),
+    });
+
+    // The fuse is 2 seconds, each update is 50ms
+    for _ in 0..30 {
+        app.update();
+    }
+    assert!(app.world().get::<ArmedFuse>(tower_id).is_some());
+    assert_eq!(player_explosions(&mut app), 0);
+
+    for _ in 0..15 {
+        app.update();
+    }
+    assert!(app.world().get::<ArmedFuse>(tower_id).is_none());
+    assert_eq!(player_explosions(&mut app), 1);
+}
+
+#[test]
+fn mirror_reflects_bullets_into_other_towers() {
+    let mut app = in_game_app_with_seed(Some(1));
+
+    let shooter_hex = Hex::new(5, 0);
+    let mirror_hex = shooter_hex + EdgeDirection::FLAT_TOP * 2;
+    // Rotated once the mirror runs top right to bottom left, bending upward shots down and right
+    let bomb_hex = mirror_hex + EdgeDirection::FLAT_BOTTOM_RIGHT * 2;
+    let shooter_id = place_tower(&mut app, "bullet2", shooter_hex, 0);
+    let mirror_id = place_tower(&mut app, "mirror", mirror_hex, 1);
+    place_tower(&mut app, "explosion1", bomb_hex, 0);
+    app.update();
+
+    app.world_mut().send_event(TriggerTowerEvent {
+        target: shooter_id,
+        trigger_history: Vec::new(),
+    });
+
+    for _ in 0..10 {
+        app.update();
+    }
+
+    let layout = app.world().resource::<Arena>().layout.clone();
+    let bomb_pos = layout.hex_to_world_pos(bomb_hex);
+    let mut query = app.world_mut().query::<(&Explosion, &Transform)>();
+    assert!(
+        query
+            .iter(app.world())
+            .any(|(explosion, transform)| explosion.team == Team::Player
+                && transform.translation.xz().distance(bomb_pos) < 0.1)
+    );
+    // The mirror isn't used up
+    assert!(app.world().get::<Tower>(mirror_id).is_some());
+}
+
+#[test]
+fn towers_with_charges_can_loop_within_a_chain() {
+    let mut app = in_game_app_with_seed(Some(1));
+
+    // Twin shots have 2 charges, facing each other they trigger each other twice
+    let first_hex = Hex::new(5, 0);
+    let towers = [first_hex, first_hex + EdgeDirection::FLAT_TOP * 2]
+        .map(|hex| place_tower(&mut app, "bullet2", hex, 0));
+    app.update();
+
+    let charges =
+        |app: &App| towers.map(|id| app.world().get::<TowerCharges>(id).unwrap().charges());
+    assert_eq!(charges(&app), [2, 2]);
+
+    app.world_mut().send_event(TriggerTowerEvent {
+        target: towers[0],
+        trigger_history: Vec::new(),
+    });
+    for _ in 0..16 {
+        app.update();
+    }
+    assert_eq!(charges(&app), [0, 0]);
+
+    // Out of charges until the 1 second cooldown brings one back
+    app.world_mut().send_event(TriggerTowerEvent {
+        target: towers[0],
+        trigger_history: Vec::new(),
+    });
+    app.update();
+    assert_eq!(charges(&app), [0, 0]);
+
+    for _ in 0..10 {
+        app.update();
+    }
+    assert_eq!(charges(&app)[0], 1);
+}
+
+#[test]
+fn enemies_destroy_towers_they_walk_into() {
+    let mut app = in_game_app_with_seed(Some(1));
+
+    let hex = Hex::new(5, 0);
+    let tower_id = place_tower(&mut app, "bullet2", hex, 0);
+    assert_eq!(app.world().get::<Health>(tower_id).unwrap().current, 3);
+
+    // Each enemy hits the tower and then explodes on it
+    for _ in 0..2 {
+        spawn_enemy(&mut app, hex, EnemyArchetype::Grunt);
+    }
+    for _ in 0..10 {
+        app.update();
+    }
+
+    assert!(app.world().get_entity(tower_id).is_err());
+    assert!(
+        !app.world()
+            .resource::<ArenaIndex>()
+            .tower_index
+            .contains_key(&hex)
+    );
+}
+
+#[test]
+fn splitters_split_into_runners_when_killed() {
+    let mut app = in_game_app_with_seed(Some(1));
+
+    let wave_enemies = enemy_ids(&mut app);
+    let splitter = spawn_enemy(&mut app, Hex::new(5, 0), EnemyArchetype::Splitter);
+    // Splitters take two hits on normal difficulty
+    assert_eq!(app.world().get::<Health>(splitter).unwrap().current, 2);
+
+    app.world_mut().send_event(DamageEvent {
+        target: splitter,
+        damage: 2,
+        chain_length: 1,
+    });
+    app.update();
+
+    assert!(app.world().get_entity(splitter).is_err());
+    let mut query = app.world_mut().query::<(Entity, &Enemy)>();
+    let new_archetypes: Vec<EnemyArchetype> = query
+        .iter(app.world())
+        .filter(|(id, _)| !wave_enemies.contains(id))
+        .map(|(_, enemy)| enemy.archetype)
+        .collect();
+    assert_eq!(
+        new_archetypes,
+        vec![EnemyArchetype::Runner, EnemyArchetype::Runner]
+    );
+}
+
+#[test]
+fn shooters_keep_their_distance_and_shoot_towers_in_the_way() {
+    let mut app = in_game_app_with_seed(Some(1));
+
+    // The tower is between the shooter and the player at the center of the arena
+    let tower_id = place_tower(&mut app, "bullet2", Hex::new(4, 0), 0);
+    let far_shooter = spawn_enemy(&mut app, Hex::new(10, 0), EnemyArchetype::Shooter);
+    // Spawned too close to the player, so it backs away
+    let near_shooter = spawn_enemy(&mut app, Hex::new(0, -2), EnemyArchetype::Shooter);
+    let near_start = app
+        .world()
+        .get::<Transform>(near_shooter)
+        .unwrap()
+        .translation;
+    assert!(near_start.xz().length() < 4.0);
+
+    for _ in 0..80 {
+        app.update();
+    }
+
+    // Shooters stop between 6 and 10 units from the player at the center of the arena
+    for shooter in [far_shooter, near_shooter] {
+        let shooter_pos = app.world().get::<Transform>(shooter).unwrap().translation;
+        let distance = shooter_pos.xz().length();
+        assert!(
+            (5.9..=10.1).contains(&distance),
+            "shooter is {distance} away"
+        );
+    }
+    assert!(app.world().get::<Health>(tower_id).unwrap().current < 3);
+}
+
+#[test]
+fn enemies_walk_around_towers() {
+    let mut app = in_game_app_with_seed(Some(1));
+
+    // A curved wall of towers between the enemy and the player at the center of the arena
+    let towers: Vec<Entity> = Hex::ZERO
+        .ring(4)
+        .filter(|hex| hex.unsigned_distance_to(Hex::new(4, 0)) <= 2)
+        .map(|hex| place_tower(&mut app, "bullet2", hex, 0))
+        .collect();
+    let enemy = spawn_enemy(&mut app, Hex::new(8, 0), EnemyArchetype::Grunt);
+    let start = app.world().get::<Transform>(enemy).unwrap().translation;
+
+    for _ in 0..60 {
+        app.update();
+    }
+
+    for tower in towers {
+        assert_eq!(app.world().get::<Health>(tower).unwrap().current, 3);
+    }
+    let enemy_pos = app.world().get::<Transform>(enemy).unwrap().translation;
+    assert!(enemy_pos.xz().length() < start.xz().length() - 5.0);
+}
+
+/// Explosions from towers, enemies also explode when they die.
+fn player_explosions(app: &mut App) -> usize {
+    let mut query = app.world_mut().query::<&Explosion>();
+    query
+        .iter(app.world())
+        .filter(|explosion| explosion.team == Team::Player)
+        .count()
+}
+
+#[test]
+fn gravity_well_pulls_enemies_towards_tower() {
+    let hex = Hex::new(5, 0);
+    // Off to the side, so walking to the player doesn't take the enemy into the tower
+    let enemy_hex = hex + EdgeDirection::FLAT_TOP_RIGHT * 2;
+
+    let distances = [false, true].map(|trigger| {
+        let mut app = in_game_app_with_seed(Some(1));
+        let tower_id = place_tower(&mut app, "gravity", hex, 0);
+        let enemy = spawn_enemy(&mut app, enemy_hex, EnemyArchetype::Grunt);
+
+        if trigger {
+            app.world_mut().send_event(TriggerTowerEvent {
+                target: tower_id,
+                trigger_history: Vec::new(),
+            });
+        }
+        // Stop while the pulled enemy is still moving in
+        for _ in 0..6 {
+            app.update();
+        }
+
+        let layout = &app.world().resource::<Arena>().layout;
+        let position = app
+            .world()
+            .get::<Transform>(enemy)
+            .unwrap()
+            .translation
+            .xz();
+        position.distance(layout.hex_to_world_pos(hex))
+    });
+
+    assert!(distances[1] < distances[0]);
+}
+
+#[test]
+fn gravity_well_survives_pulling_enemies_for_its_full_duration() {
+    let mut app = in_game_app_with_seed(Some(1));
+    let hex = Hex::new(5, 0);
+    let tower = place_tower(&mut app, "gravity", hex, 0);
+    // Further from the player than the well, so walking to the player takes them past it
+    let enemies = [Hex::new(2, 0), Hex::new(2, -2), Hex::new(0, 2)]
+        .map(|offset| spawn_enemy(&mut app, hex + offset, EnemyArchetype::Grunt));
+    let centre = app.world().resource::<Arena>().layout.hex_to_world_pos(hex);
+
+    app.world_mut().send_event(TriggerTowerEvent {
+        target: tower,
+        trigger_history: Vec::new(),
+    });
+    // The well pulls for 3 seconds
+    let mut checked = 0;
+    for update in 0..70 {
+        app.update();
+
+        let alive: Vec<Vec3> = enemies
+            .iter()
+            .filter_map(|enemy| app.world().get::<Transform>(*enemy))
+            .map(|transform| transform.translation)
+            .collect();
+        // Too far from the player to reach it within the first second
+        if update < 20 {
+            assert_eq!(alive.len(), enemies.len());
+        }
+        for position in alive {
+            assert!(position.xz().distance(centre) > 1.0);
+            checked += 1;
+        }
+    }
+
+    assert!(checked >= enemies.len() * 20);
+    assert_eq!(app.world().get::<Health>(tower).unwrap().current, 3);
+}
+
+#[test]
+fn picked_up_tower_returns_to_collection() {
+    let mut app = in_game_app();
+    let hex = Hex::new(2, 1);
+    let id = TowerId::from("bullet2");
+    app.world_mut()
+        .resource_mut::<BuildingSettings>()
+        .towers
+        .push(CollectedTower::new(id.clone()));
+
+    BuildTowerCommand {
+        index: 0,
+        hex,
+        rotation: 3,
+    }
+    .apply(app.world_mut());
+    assert!(app.world().resource::<ArenaIndex>().is_occupied(&hex));
+    assert!(app.world().resource::<BuildingSettings>().towers.is_empty());
+
+    PickUpTowerCommand { hex }.apply(app.world_mut());
+    app.update();
+
+    assert!(!app.world().resource::<ArenaIndex>().is_occupied(&hex));
+    assert_eq!(count::<With<Tower>>(&mut app), 0);
+    let settings = app.world().resource::<BuildingSettings>();
+    assert_eq!(settings.towers, vec![CollectedTower::new(id)]);
+    assert_eq!(settings.selected_tower, Some(0));
+    assert_eq!(settings.rotation, 3);
+}
+
+#[test]
+fn picked_up_tower_can_be_moved_to_another_hex() {
+    let mut app = in_game_app();
+    let from = Hex::new(2, 1);
+    let to = Hex::new(-3, 2);
+    let id = TowerId::from("bullet2");
+    app.world_mut()
+        .resource_mut::<BuildingSettings>()
+        .towers
+        .push(CollectedTower::new(id.clone()));
+    BuildTowerCommand {
+        index: 0,
+        hex: from,
+        rotation: 3,
+    }
+    .apply(app.world_mut());
+
+    // Confirming a move picks the tower up and builds it again where it's going
+    PickUpTowerCommand { hex: from }.apply(app.world_mut());
+    BuildTowerCommand {
+        index: 0,
+        hex: to,
+        rotation: 5,
+    }
+    .apply(app.world_mut());
+    app.update();
+
+    let tower_index = &app.world().resource::<ArenaIndex>().tower_index;
+    assert!(!tower_index.contains_key(&from));
+    let tower = app.world().get::<Tower>(tower_index[&to]).unwrap();
+    assert_eq!(tower.id, id);
+    assert_eq!(tower.rotation, 5);
+    assert_eq!(count::<With<Tower>>(&mut app), 1);
+    assert!(app.world().resource::<BuildingSettings>().towers.is_empty());
+}
+
+#[test]
+fn moved_tower_keeps_its_damage_and_charges() {
+    let mut app = in_game_app_with_seed(Some(1));
+    let from = Hex::new(2, 1);
+    let to = Hex::new(-3, 2);
+    let tower = place_tower(&mut app, "bullet2", from, 0);
+    app.world_mut().send_event(DamageEvent {
+        target: tower,
+        damage: 1,
+        chain_length: 1,
+    });
+    app.update();
+    app.world_mut()
+        .get_mut::<TowerCharges>(tower)
+        .unwrap()
+        .set_charges(1);
+
+    PickUpTowerCommand { hex: from }.apply(app.world_mut());
+    assert!(
+        app.world().resource::<BuildingSettings>().towers[0]
+            .wear
+            .is_some()
+    );
+    BuildTowerCommand {
+        index: 0,
+        hex: to,
+        rotation: 0,
+    }
+    .apply(app.world_mut());
+
+    let moved = app.world().resource::<ArenaIndex>().tower_index[&to];
+    assert_eq!(app.world().get::<Health>(moved).unwrap().current, 2);
+    assert_eq!(app.world().get::<TowerCharges>(moved).unwrap().charges(), 1);
+}
+
+#[test]
+fn building_duplicate_tower_upgrades_it() {
+    let mut app = in_game_app();
+    let hex = Hex::new(2, 1);
+    let id = TowerId::from("bullet2");
+    app.world_mut()
+        .resource_mut::<BuildingSettings>()
+        .towers
+        .extend([CollectedTower::new(id.clone()), CollectedTower::new(id)]);
+
+    for rotation in [2, 4] {
+        BuildTowerCommand {
+            index: 0,
+            hex,
+            rotation,
+        }
+        .apply(app.world_mut());
+    }
+    app.update();
+
+    assert!(app.world().resource::<BuildingSettings>().towers.is_empty());
+    let mut query = app.world_mut().query::<&Tower>();
+    let towers: Vec<&Tower> = query.iter(app.world()).collect();
+    assert_eq!(towers.len(), 1);
+    assert_eq!(towers[0].level, 2);
+    assert_eq!(towers[0].rotation, 2);
+}
+
+fn player_position(app: &mut App) -> Vec3 {
+    let mut query = app.world_mut().query_filtered::<&Transform, With<Player>>();
+    query.single(app.world()).unwrap().translation
+}
+
+#[test]
+fn replay_reproduces_recorded_run() {
+    // Advance exactly one fixed tick per update so the replay can stop on the last recorded tick
+    let step = Time::<Fixed>::default().timestep();
+    let mut recorded = start_run(headless_app_with_step(step), Some(7));
+    {
+        let world = recorded.world_mut();
+        world
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .press(KeyCode::KeyW);
+        world
+            .resource_mut::<ButtonInput<MouseButton>>()
+            .press(MouseButton::Left);
+        world.resource_mut::<PlayerInput>().gun_angle = 1.0;
+    }
+    for _ in 0..120 {
+        recorded.update();
+    }
+    let replay = recorded.world().resource::<ReplayRecorder>().replay.clone();
+
+    let mut replayed = headless_app_with_step(step);
+    replayed.insert_resource(ReplayPlayback::new(replay));
+    update_until_state(&mut replayed, AppState::InGame);
+    for _ in 0..1000 {
+        if replayed.world().resource::<ReplayPlayback>().is_finished() {
+            break;
+        }
+        replayed.update();
+    }
+
+    assert_ne!(player_position(&mut recorded), Vec3::ZERO);
+    assert_eq!(
+        player_position(&mut recorded),
+        player_position(&mut replayed)
+    );
+    assert_eq!(
+        enemy_positions(&mut recorded),
+        enemy_positions(&mut replayed)
+    );
+    assert_eq!(
+        recorded.world().resource::<PlayerScore>().score,
+        replayed.world().resource::<PlayerScore>().score
+    );
+}